@@ -0,0 +1,181 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use jenkenpwn::core::client::DEFAULT_MAX_REQUESTS;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+pub struct Cli {
+    /// Verbose mode
+    #[arg(short, long)]
+    pub verbose: bool,
+    /// Do not verify SSL certificate
+    #[arg(short, long)]
+    pub insecure: bool,
+    #[command(subcommand)]
+    pub subcmd: SubCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SubCommand {
+    /// Dump jobs and builds data
+    Dump(Box<DumpArgs>),
+    /// Scan dumped builds for secrets
+    Scan(ScanArgs),
+    /// Report the current identity and what it is allowed to do
+    Whoami(WhoamiArgs),
+    /// Compare what is visible without authentication and with the given
+    /// credentials
+    Compare(CompareArgs),
+    /// Find the Jenkins version and the security advisories applying to it
+    Fingerprint(FingerprintArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct DumpArgs {
+    #[command(flatten)]
+    pub auth: AuthArgs,
+    /// Recover from server failure, skiping already downloaded builds
+    #[arg(short, long)]
+    pub recover: bool,
+    /// Scan builds for secrets while dumping, saving findings to
+    /// findings.jsonl
+    #[arg(short, long)]
+    pub scan: bool,
+    /// Output directory
+    #[arg(short, long, default_value = "dumps")]
+    pub output: String,
+    /// Dump only the last build of each job
+    #[arg(short, long)]
+    pub last: bool,
+    /// Maximum number of builds dumped per job, the most recent first
+    /// [default: all]
+    #[arg(short, long)]
+    pub max_builds: Option<usize>,
+    /// Read jobs from a jobs or views dump file, or builds from an errors.json
+    #[arg(short, long)]
+    pub jobs: Option<String>,
+    /// Maximum number of requests in flight
+    #[arg(short = 'c', long, default_value_t = DEFAULT_MAX_REQUESTS)]
+    pub max_requests: usize,
+    /// Maximum number of requests per second [default: unlimited]
+    #[arg(long)]
+    pub rps: Option<f64>,
+    /// Advisory database to match plugins against instead of the bundled one
+    #[arg(short, long)]
+    pub advisories: Option<String>,
+    /// Resources to dump
+    pub resource: DumpResource,
+    /// Url of the jenkins server
+    pub url: String,
+}
+
+#[derive(Args, Debug)]
+pub struct WhoamiArgs {
+    #[command(flatten)]
+    pub auth: AuthArgs,
+    /// Output directory, where whoami.json is saved
+    #[arg(short, long, default_value = "dumps")]
+    pub output: String,
+    /// Url of the jenkins server
+    pub url: String,
+}
+
+#[derive(Args, Debug)]
+pub struct CompareArgs {
+    #[command(flatten)]
+    pub auth: AuthArgs,
+    /// Output directory, where visibility.json and the jobs and views of both
+    /// runs are saved
+    #[arg(short, long, default_value = "dumps")]
+    pub output: String,
+    /// Discover only the last build of each job
+    #[arg(short, long)]
+    pub last: bool,
+    /// Maximum number of requests in flight
+    #[arg(short = 'c', long, default_value_t = DEFAULT_MAX_REQUESTS)]
+    pub max_requests: usize,
+    /// Maximum number of requests per second [default: unlimited]
+    #[arg(long)]
+    pub rps: Option<f64>,
+    /// Url of the jenkins server
+    pub url: String,
+}
+
+#[derive(Args, Debug)]
+pub struct FingerprintArgs {
+    #[command(flatten)]
+    pub auth: AuthArgs,
+    /// Output directory, where fingerprint.json is saved
+    #[arg(short, long, default_value = "dumps")]
+    pub output: String,
+    /// Advisory database to use instead of the bundled one
+    #[arg(short, long)]
+    pub advisories: Option<String>,
+    /// Url of the jenkins server
+    pub url: String,
+}
+
+/// Credentials shared by the subcommands talking to the server
+#[derive(Args, Debug)]
+pub struct AuthArgs {
+    /// Username for authentication
+    #[arg(short, long, env = "JENKINS_USERNAME")]
+    pub username: Option<String>,
+    /// Password for authentication, prompted for when a username is given
+    /// without password or token
+    #[arg(short, long, env = "JENKINS_PASSWORD", hide_env_values = true)]
+    pub password: Option<String>,
+    /// API token for authentication, used along with the username
+    #[arg(short, long, env = "JENKINS_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+    /// Session cookies for authentication (e.g. "JSESSIONID.abcd=...")
+    #[arg(long, env = "JENKINS_COOKIE", hide_env_values = true)]
+    pub cookie: Option<String>,
+    /// Bearer token for authentication
+    #[arg(long, env = "JENKINS_BEARER", hide_env_values = true)]
+    pub bearer: Option<String>,
+    /// Custom header for authentication (e.g. "X-Forwarded-User: admin")
+    #[arg(long, value_parser = parse_header, env = "JENKINS_HEADER", hide_env_values = true)]
+    pub header: Option<(String, String)>,
+    /// Read username and password for the server host from a netrc file
+    #[arg(short, long, env = "JENKINS_NETRC")]
+    pub netrc: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ScanArgs {
+    /// Output file for findings [default: <DIRECTORY>/findings.json]
+    #[arg(short, long)]
+    pub output: Option<String>,
+    /// Dump directory to scan
+    #[arg(default_value = "dumps")]
+    pub directory: String,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum DumpResource {
+    /// Dump builds
+    Builds,
+    /// Dump jobs
+    Jobs,
+    /// Dump views
+    Views,
+    /// Dump installed plugins and the advisories affecting them
+    Plugins,
+    /// Dump users, from the people pages and the builds already dumped
+    Users,
+    /// Dump nodes, with the jobs of the builds already dumped that ran on them
+    Nodes,
+    /// Dump credential metadata, with the jobs referencing each credential
+    Credentials,
+}
+
+/// Parse a header in the "Name: value" format
+fn parse_header(header: &str) -> Result<(String, String), String> {
+    match header.split_once(':') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err("expected \"Name: value\"".to_string()),
+    }
+}
@@ -0,0 +1,257 @@
+use crate::error::{Error, Result};
+use crate::utils::load_json;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Advisory database shipped with the tool. A newer copy with the same format
+/// can be given at runtime instead.
+const BUNDLED_DATABASE: &str = include_str!("../../data/advisories.json");
+
+/// Offline database of Jenkins security advisories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvisoryDatabase {
+    /// Date of the most recent advisory included (e.g. "2024-08-07")
+    pub updated: String,
+    #[serde(default)]
+    pub core: Vec<CoreAdvisory>,
+    #[serde(default)]
+    pub plugins: Vec<PluginAdvisory>,
+}
+
+/// Security advisory affecting Jenkins core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreAdvisory {
+    /// Jenkins issue identifier (e.g. "SECURITY-3314")
+    pub id: String,
+    #[serde(default)]
+    pub cves: Vec<String>,
+    pub title: String,
+    pub severity: String,
+    /// Publication date of the advisory
+    pub date: String,
+    pub url: String,
+    /// First weekly release with the fix
+    pub fixed: String,
+    /// First release with the fix on each LTS line
+    #[serde(default)]
+    pub fixed_lts: Vec<String>,
+    /// First affected release, when older releases are not affected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub introduced: Option<String>,
+}
+
+/// Security advisory affecting a plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginAdvisory {
+    /// Jenkins issue identifier (e.g. "SECURITY-1266")
+    pub id: String,
+    #[serde(default)]
+    pub cves: Vec<String>,
+    /// Short name of the affected plugin (e.g. "script-security")
+    pub plugin: String,
+    pub title: String,
+    pub severity: String,
+    /// Publication date of the advisory
+    pub date: String,
+    pub url: String,
+    /// First release with the fix, missing if the plugin is not fixed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<String>,
+}
+
+impl AdvisoryDatabase {
+    /// Load the database shipped with the tool
+    pub fn bundled() -> Result<Self> {
+        serde_json::from_str(BUNDLED_DATABASE)
+            .map_err(|e| Error::parse("bundled advisory database", e))
+    }
+
+    /// Load the database from the given file, or the bundled one if none is
+    /// given
+    pub fn load(filename: Option<&str>) -> Result<Self> {
+        match filename {
+            Some(filename) => load_json(filename),
+            None => Self::bundled(),
+        }
+    }
+
+    /// Get the core advisories affecting the given version
+    pub fn core_advisories(&self, version: &Version) -> Vec<&CoreAdvisory> {
+        self.core
+            .iter()
+            .filter(|advisory| advisory.affects(version))
+            .collect()
+    }
+
+    /// Get the advisories affecting the given plugin. All advisories of the
+    /// plugin are returned when its version is unknown.
+    pub fn plugin_advisories(&self, plugin: &str, version: Option<&str>) -> Vec<&PluginAdvisory> {
+        let version = version.and_then(Version::parse);
+        self.plugins
+            .iter()
+            .filter(|advisory| advisory.plugin == plugin)
+            .filter(|advisory| match (&version, &advisory.fixed) {
+                (Some(version), Some(fixed)) => {
+                    Version::parse(fixed).is_some_and(|fixed| *version < fixed)
+                }
+                _ => true,
+            })
+            .collect()
+    }
+}
+
+impl CoreAdvisory {
+    /// Check whether the given weekly or LTS release is affected
+    pub fn affects(&self, version: &Version) -> bool {
+        if let Some(introduced) = self.introduced.as_deref().and_then(Version::parse) {
+            if *version < introduced {
+                return false;
+            }
+        }
+        if !version.is_lts() {
+            return Version::parse(&self.fixed).is_some_and(|fixed| *version < fixed);
+        }
+        let fixed_lts: Vec<Version> = self
+            .fixed_lts
+            .iter()
+            .filter_map(|fixed| Version::parse(fixed))
+            .collect();
+        // without any backport, lines before the fixed weekly release are affected
+        if fixed_lts.is_empty() {
+            return Version::parse(&self.fixed).is_some_and(|fixed| version.line() < fixed.line());
+        }
+        // LTS lines are fixed by backports, a line without backport is only
+        // affected if a later line had to be fixed
+        match fixed_lts
+            .iter()
+            .find(|fixed| fixed.line() == version.line())
+        {
+            Some(fixed) => version < fixed,
+            None => fixed_lts.iter().any(|fixed| fixed.line() > version.line()),
+        }
+    }
+}
+
+/// Jenkins release number (e.g. "2.442" for a weekly release, "2.426.3" for
+/// an LTS release)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(Vec<u64>);
+
+impl Version {
+    /// Parse the leading numeric components of a version, ignoring suffixes
+    /// such as "-SNAPSHOT" or "-rc"
+    pub fn parse(version: &str) -> Option<Self> {
+        let components: Vec<u64> = version
+            .trim()
+            .split(['.', '-'])
+            .map_while(|component| component.parse().ok())
+            .collect();
+        if components.is_empty() {
+            None
+        } else {
+            Some(Version(components))
+        }
+    }
+
+    /// Check whether this is an LTS release, numbered after the weekly
+    /// release it is based on
+    pub fn is_lts(&self) -> bool {
+        self.0.len() >= 3
+    }
+
+    /// Get the release line, which is the weekly release an LTS release is
+    /// based on
+    pub fn line(&self) -> &[u64] {
+        &self.0[..self.0.len().min(2)]
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let components: Vec<String> = self.0.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", components.join("."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advisory(fixed: &str, fixed_lts: &[&str], introduced: Option<&str>) -> CoreAdvisory {
+        CoreAdvisory {
+            id: "SECURITY-0".to_string(),
+            cves: Vec::new(),
+            title: String::new(),
+            severity: "high".to_string(),
+            date: String::new(),
+            url: String::new(),
+            fixed: fixed.to_string(),
+            fixed_lts: fixed_lts.iter().map(|fixed| fixed.to_string()).collect(),
+            introduced: introduced.map(|introduced| introduced.to_string()),
+        }
+    }
+
+    fn affects(advisory: &CoreAdvisory, version: &str) -> bool {
+        advisory.affects(&Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn parse_versions() {
+        assert!(!Version::parse("2.442").unwrap().is_lts());
+        assert!(Version::parse("2.426.3").unwrap().is_lts());
+        assert!(!Version::parse("2.443-SNAPSHOT").unwrap().is_lts());
+        assert_eq!(Version::parse("2.426.3").unwrap().line(), &[2, 426]);
+        assert_eq!(Version::parse("1.395").unwrap().to_string(), "1.395");
+        assert!(Version::parse("unknown").is_none());
+    }
+
+    #[test]
+    fn weekly_releases() {
+        let advisory = advisory("2.442", &["2.426.3"], None);
+        assert!(affects(&advisory, "2.441"));
+        assert!(affects(&advisory, "1.651"));
+        assert!(!affects(&advisory, "2.442"));
+        assert!(!affects(&advisory, "2.460"));
+    }
+
+    #[test]
+    fn lts_backport_lines() {
+        let advisory = advisory("2.471", &["2.452.4", "2.462.1"], None);
+        // lines with a backport are fixed from the backport on
+        assert!(affects(&advisory, "2.452.3"));
+        assert!(!affects(&advisory, "2.452.4"));
+        assert!(!affects(&advisory, "2.462.1"));
+        // older lines without backport are affected
+        assert!(affects(&advisory, "2.440.3"));
+        // newer lines are based on a fixed weekly release
+        assert!(!affects(&advisory, "2.479.1"));
+    }
+
+    #[test]
+    fn lts_without_backport() {
+        let advisory = advisory("2.442", &[], None);
+        assert!(affects(&advisory, "2.426.3"));
+        assert!(affects(&advisory, "2.440.1"));
+        assert!(!affects(&advisory, "2.442.1"));
+        assert!(!affects(&advisory, "2.452.4"));
+    }
+
+    #[test]
+    fn introduced_release() {
+        let advisory = advisory("2.442", &["2.426.3"], Some("2.400"));
+        assert!(!affects(&advisory, "2.399"));
+        assert!(affects(&advisory, "2.400"));
+        assert!(!affects(&advisory, "2.387.3"));
+        assert!(affects(&advisory, "2.414.3"));
+        assert!(!affects(&advisory, "2.426.3"));
+    }
+
+    #[test]
+    fn bundled_database() {
+        let database = AdvisoryDatabase::bundled().unwrap();
+        assert!(database
+            .core
+            .iter()
+            .all(|advisory| Version::parse(&advisory.fixed).is_some()));
+    }
+}
@@ -1,106 +1,657 @@
-use crate::logger::init_logger;
-use crate::utils::concatenate_url;
-use log::debug;
-use reqwest::Client;
+use super::limiter::Limiter;
+use crate::error::{AuthError, Error, Result};
+use crate::utils::{concatenate_url, normalize_base_url, redact_url};
+use log::{debug, info, warn};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Default maximum number of requests in flight
+pub const DEFAULT_MAX_REQUESTS: usize = 20;
+/// Number of times a throttled request is retried before giving up
+const MAX_THROTTLED_RETRIES: usize = 5;
 
 pub struct JenkinsClient {
     client: Client,
     url: String,
-    credentials: Option<Credentials>,
+    /// Root url configured in Jenkins, used in the urls returned by the API
+    root_url: Option<String>,
+    /// Set once the user has been warned that returned urls are rewritten
+    rewrite_warned: AtomicBool,
+    authentication: Option<Authentication>,
+    limiter: Limiter,
+    /// CSRF crumb, `None` until fetched. `Some(None)` means the server has no
+    /// CSRF protection.
+    crumb: Mutex<Option<Option<Crumb>>>,
 }
 
-struct Credentials {
-    username: Option<String>,
-    password: Option<String>,
+/// CSRF crumb required by POST requests, tied to the session cookie
+#[derive(Clone)]
+struct Crumb {
+    field: String,
+    value: String,
 }
 
-impl JenkinsClient {
-    /// Create a new JenkinsClient without credentials
-    pub fn new(url: String, verbose: bool, insecure: bool) -> Self {
-        init_logger(verbose);
-        let client = if insecure {
-            Client::builder().danger_accept_invalid_certs(true).build().unwrap()
-        } else {
-            Client::new()
-        };
-        Self {
+/// Response returned as is, without checking its status
+pub struct Page {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// Url of the response, after redirects
+    pub url: Url,
+    pub body: String,
+}
+
+impl Page {
+    /// Get the value of the given header, if it is valid text
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+}
+
+/// How requests are authenticated against the server
+pub enum Authentication {
+    /// Username and password, sent as basic auth
+    Basic { username: String, password: String },
+    /// Username and API token, sent as basic auth
+    ApiToken { username: String, token: String },
+    /// Session cookies taken from a browser (e.g. "JSESSIONID.abcd=...")
+    Cookie(String),
+    /// Arbitrary header, e.g. set by a reverse proxy
+    Header { name: String, value: String },
+    /// Bearer token, e.g. from an OIDC provider
+    Bearer(String),
+}
+
+/// Builder for a JenkinsClient
+pub struct ClientBuilder {
+    url: String,
+    authentication: Option<Authentication>,
+    insecure: bool,
+    max_requests: usize,
+    requests_per_second: Option<f64>,
+}
+
+impl ClientBuilder {
+    /// Authenticate requests with the given authentication
+    pub fn authentication(mut self, authentication: Authentication) -> Self {
+        self.authentication = Some(authentication);
+        self
+    }
+
+    /// Do not verify the server certificate
+    pub fn insecure(mut self, insecure: bool) -> Self {
+        self.insecure = insecure;
+        self
+    }
+
+    /// Maximum number of requests in flight
+    pub fn max_requests(mut self, max_requests: usize) -> Self {
+        self.max_requests = max_requests;
+        self
+    }
+
+    /// Maximum number of requests per second, unlimited if `None`
+    pub fn requests_per_second(mut self, requests_per_second: Option<f64>) -> Self {
+        self.requests_per_second = requests_per_second;
+        self
+    }
+
+    /// Build the JenkinsClient
+    pub fn build(self) -> Result<JenkinsClient> {
+        // crumbs are only valid along with the session cookie they were
+        // issued for
+        let client = Client::builder()
+            .cookie_store(true)
+            .danger_accept_invalid_certs(self.insecure)
+            .build()
+            .map_err(|source| Error::Http {
+                url: redact_url(&self.url),
+                source,
+            })?;
+        Ok(JenkinsClient {
             client,
-            url,
-            credentials: None,
+            url: normalize_base_url(&self.url),
+            root_url: None,
+            rewrite_warned: AtomicBool::new(false),
+            authentication: self.authentication,
+            limiter: Limiter::new(self.max_requests, self.requests_per_second),
+            crumb: Mutex::new(None),
+        })
+    }
+}
+
+impl JenkinsClient {
+    /// Start building a JenkinsClient for the given url, without credentials
+    /// and with the default limits
+    pub fn builder(url: impl Into<String>) -> ClientBuilder {
+        ClientBuilder {
+            url: url.into(),
+            authentication: None,
+            insecure: false,
+            max_requests: DEFAULT_MAX_REQUESTS,
+            requests_per_second: None,
         }
     }
 
-    /// Create a new JenkinsClient with credentials
-    pub fn with_credentials(
-        url: String,
-        username: String,
-        password: String,
-        verbose: bool,
-        insecure: bool
-    ) -> Self {
-        init_logger(verbose);
-        let client = if insecure {
-            Client::builder().danger_accept_invalid_certs(true).build().unwrap()
-        } else {
-            Client::new()
-        };
-        Self {
-            client,
-            url,
-            credentials: Some(Credentials {
-                username: Some(username),
-                password: Some(password),
-            }),
+    /// Base url of the server, updated once its root is detected
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Maximum number of requests in flight
+    pub fn max_requests(&self) -> usize {
+        self.limiter.max_requests()
+    }
+
+    /// Detect the root of the Jenkins instance, which may be installed under a
+    /// context path (e.g. "https://host/jenkins/"). The given url and each of
+    /// its parents are probed until the root object answers, following
+    /// redirects. The base url is kept if the root cannot be confirmed.
+    pub async fn detect_root(&mut self) -> Result<()> {
+        let mut candidate = Url::parse(&self.url).map_err(|source| Error::Url {
+            url: redact_url(&self.url),
+            source,
+        })?;
+        loop {
+            let url = concatenate_url(candidate.as_str(), "api/json?tree=url")?;
+            debug!("Probing for Jenkins root: {}", redact_url(&url));
+            let page = self.send(&url, true, || self.client.get(&url)).await?;
+            let is_jenkins =
+                page.headers.contains_key("X-Jenkins") || page.headers.contains_key("X-Hudson");
+            if is_jenkins && page.status.is_success() {
+                let mut root = page.url.clone();
+                let json: serde_json::Value = serde_json::from_str(&page.body).unwrap_or_default();
+                // the root object is reported with the legacy class name
+                if json.get("_class").and_then(|c| c.as_str()) == Some("hudson.model.Hudson") {
+                    root.set_query(None);
+                    let path = root.path().trim_end_matches("api/json").to_string();
+                    root.set_path(&path);
+                    if root.as_str() != self.url {
+                        info!("Jenkins root detected at {}", redact_url(root.as_str()));
+                        self.url = root.to_string();
+                    }
+                    if let Some(root_url) = json.get("url").and_then(|url| url.as_str()) {
+                        let root_url = normalize_base_url(root_url);
+                        if root_url != self.url {
+                            self.warn_rewrite(&root_url);
+                        }
+                        self.root_url = Some(root_url);
+                    }
+                    return Ok(());
+                }
+            }
+
+            // try the parent path
+            let path = candidate.path().trim_end_matches('/').to_string();
+            match path.rfind('/') {
+                Some(index) if !path.is_empty() => {
+                    candidate.set_path(&path[..=index]);
+                }
+                _ => break,
+            }
+        }
+        debug!(
+            "Could not detect Jenkins root, using {}",
+            redact_url(&self.url)
+        );
+        Ok(())
+    }
+
+    /// Map a url returned by the API back onto the base url. Jenkins builds
+    /// its urls from the configured root url, which may be an internal
+    /// address not reachable from here, so only the path is kept. Urls
+    /// already under the base url, like those built by the client, are kept
+    /// as is.
+    pub fn rewrite_url(&self, url: &str) -> String {
+        if url.starts_with(self.url.as_str()) {
+            return url.to_string();
+        }
+        if let Some(root_url) = &self.root_url {
+            if let Some(path) = url.strip_prefix(root_url.as_str()) {
+                return format!("{}{}", self.url, path);
+            }
+        }
+        // root url unknown, swap the origin and keep the context path of the
+        // base url, which the returned url may lack
+        match (Url::parse(url), Url::parse(&self.url)) {
+            (Ok(mut parsed), Ok(base)) if parsed.origin() != base.origin() => {
+                self.warn_rewrite(url);
+                if !parsed.path().starts_with(base.path()) {
+                    let path = format!("{}{}", base.path(), parsed.path().trim_start_matches('/'));
+                    parsed.set_path(&path);
+                }
+                let _ = parsed.set_scheme(base.scheme());
+                let _ = parsed.set_host(base.host_str());
+                let _ = parsed.set_port(base.port());
+                parsed.to_string()
+            }
+            _ => url.to_string(),
+        }
+    }
+
+    /// Warn, only once, that returned urls differ from the base url
+    fn warn_rewrite(&self, url: &str) {
+        if !self.rewrite_warned.swap(true, Ordering::Relaxed) {
+            warn!(
+                "Jenkins reports urls under {} instead of {}, rewriting them",
+                redact_url(url),
+                redact_url(&self.url)
+            );
         }
     }
 
     /// Perform a GET request to the given path and return the response as a string
-    pub async fn get_path(&self, path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    pub async fn get_path(&self, path: &str) -> Result<String> {
         let url = concatenate_url(&self.url, path)?;
-        debug!("GET {}", url);
-        let mut request = self.client.get(&url);
+        self.get_url(&url).await
+    }
 
-        if let Some(credentials) = &self.credentials {
-            request =
-                request.basic_auth(credentials.get_username(), Some(credentials.get_password()));
+    /// Perform a GET request to the given url and return the response as a
+    /// string
+    pub async fn get_url(&self, url: &str) -> Result<String> {
+        let url = &self.rewrite_url(url);
+        debug!("GET {}", redact_url(url));
+        let page = self.send(url, true, || self.client.get(url)).await?;
+        if let Some(error) = self.response_error(url, &page) {
+            return Err(error);
         }
+        Ok(page.body)
+    }
 
-        let response = request.send().await?;
-        if response.status().is_success() {
-            Ok(response.text().await?)
-        } else {
-            Err(format!("Error: {}", response.status()).into())
+    /// Perform a GET request to the given path and parse the JSON response
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = concatenate_url(&self.url, path)?;
+        self.get_json_url(&url).await
+    }
+
+    /// Perform a GET request to the given url and parse the JSON response
+    pub async fn get_json_url<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self.get_url(url).await?;
+        serde_json::from_str(&response).map_err(|e| Error::parse(error_url(url), e))
+    }
+
+    /// Perform a GET request to the given path and return the response
+    /// whatever its status, for callers interested in the headers or in
+    /// error pages
+    pub async fn get_page(&self, path: &str) -> Result<Page> {
+        let url = concatenate_url(&self.url, path)?;
+        debug!("GET {}", redact_url(&url));
+        self.send(&url, true, || self.client.get(&url)).await
+    }
+
+    /// Perform a POST request without body to the given path and return the
+    /// response as a string
+    pub async fn post_path(&self, path: &str) -> Result<String> {
+        self.post_form(path, &[]).await
+    }
+
+    /// Perform a POST request with a form to the given path and return the
+    /// response as a string. The CSRF crumb is added to the request, and
+    /// refreshed once if the server rejects it. The request is not replayed
+    /// when throttled, unless the server tells when to retry, as it may
+    /// trigger an action such as a build.
+    pub async fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        let url = concatenate_url(&self.url, path)?;
+        debug!("POST {}", redact_url(&url));
+        let mut refreshed = false;
+        loop {
+            let crumb = self.crumb(refreshed).await?;
+            let page = self
+                .send(&url, false, || {
+                    let request = self.client.post(&url).form(form);
+                    match &crumb {
+                        Some(crumb) => request.header(crumb.field.as_str(), crumb.value.as_str()),
+                        None => request,
+                    }
+                })
+                .await?;
+            let Some(error) = self.response_error(&url, &page) else {
+                return Ok(page.body);
+            };
+            if page.status == StatusCode::FORBIDDEN
+                && !refreshed
+                && page.body.contains("No valid crumb")
+            {
+                debug!("Crumb rejected, refreshing it");
+                refreshed = true;
+                continue;
+            }
+            return Err(error);
         }
     }
 
-    /// Perform a GET request to the given url and return the response as a
-    /// string
-    pub async fn get_url(&self, url: &str) -> Result<String, Box<dyn std::error::Error>> {
-        debug!("GET {}", url);
-        let mut request = self.client.get(url);
+    /// Get the typed error for an unsuccessful response, based on its status,
+    /// the headers Jenkins sets on authentication and permission failures, and
+    /// redirects to a login page
+    fn response_error(&self, url: &str, page: &Page) -> Option<Error> {
+        let url = error_url(url);
+        let status = page.status;
+        let header = |name: &str| page.header(name).map(|value| value.to_string());
+        let user = header("X-You-Are-Authenticated-As");
+        let anonymous = user.as_deref().unwrap_or("anonymous") == "anonymous";
+
+        if status.is_success() {
+            if !is_login_redirect(&url, &page.url) {
+                return None;
+            }
+            let kind = if self.authentication.is_some() {
+                AuthError::BadCredentials
+            } else {
+                AuthError::Unauthenticated
+            };
+            return Some(Error::Auth { url, kind });
+        }
+        let kind = match status {
+            StatusCode::UNAUTHORIZED if self.authentication.is_some() => AuthError::BadCredentials,
+            StatusCode::UNAUTHORIZED => AuthError::Unauthenticated,
+            // credentials that were sent but ignored, like an expired session
+            // cookie, leave us anonymous
+            StatusCode::FORBIDDEN if anonymous && self.authentication.is_some() => {
+                AuthError::BadCredentials
+            }
+            StatusCode::FORBIDDEN if anonymous => AuthError::Unauthenticated,
+            StatusCode::FORBIDDEN => AuthError::MissingPermission {
+                user,
+                permission: header("X-Required-Permission"),
+            },
+            status => return Some(Error::Status { url, status }),
+        };
+        Some(Error::Auth { url, kind })
+    }
 
-        if let Some(credentials) = &self.credentials {
-            request =
-                request.basic_auth(credentials.get_username(), Some(credentials.get_password()));
+    /// Get the CSRF crumb, fetching it from the crumb issuer if it is not
+    /// cached yet or `refresh` is set
+    async fn crumb(&self, refresh: bool) -> Result<Option<Crumb>> {
+        let mut cached = self.crumb.lock().await;
+        if let (Some(crumb), false) = (cached.as_ref(), refresh) {
+            return Ok(crumb.clone());
         }
 
-        let response = request.send().await?;
-        if response.status().is_success() {
-            Ok(response.text().await?)
+        let url = concatenate_url(&self.url, "crumbIssuer/api/json")?;
+        debug!("Retrieving crumb from: {}", redact_url(&url));
+        let page = self.send(&url, true, || self.client.get(&url)).await?;
+        // no crumb issuer, CSRF protection is disabled
+        let crumb = if page.status == StatusCode::NOT_FOUND {
+            None
         } else {
-            Err(format!("Error: {}", response.status()).into())
+            if let Some(error) = self.response_error(&url, &page) {
+                return Err(error);
+            }
+            let json: serde_json::Value =
+                serde_json::from_str(&page.body).map_err(|e| Error::parse(error_url(&url), e))?;
+            match (
+                json.get("crumbRequestField").and_then(|f| f.as_str()),
+                json.get("crumb").and_then(|c| c.as_str()),
+            ) {
+                (Some(field), Some(value)) => Some(Crumb {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                }),
+                _ => return Err(Error::parse(error_url(&url), "missing crumb fields")),
+            }
+        };
+        *cached = Some(crumb.clone());
+        Ok(crumb)
+    }
+
+    /// Send a request through the limiter, retrying while the server is
+    /// throttling us. The request is rebuilt on each attempt. Requests that
+    /// are not idempotent are only replayed when the server sent Retry-After,
+    /// which means the request was not processed. The body is read before
+    /// the permit is released, so that slow downloads count against the
+    /// limit.
+    async fn send<F>(&self, url: &str, idempotent: bool, build_request: F) -> Result<Page>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            let mut request = build_request();
+            if let Some(authentication) = &self.authentication {
+                request = authentication.apply(request);
+            }
+
+            let _permit = self.limiter.acquire().await;
+            let response = request.send().await.map_err(|source| Error::Http {
+                url: error_url(url),
+                source: source.without_url(),
+            })?;
+            let status = response.status();
+            if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::SERVICE_UNAVAILABLE
+            {
+                self.limiter.succeeded();
+                return read_page(url, response).await;
+            }
+
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+                .map(Duration::from_secs);
+            self.limiter.throttled(retry_after);
+            attempt += 1;
+            if attempt > MAX_THROTTLED_RETRIES || (!idempotent && retry_after.is_none()) {
+                return read_page(url, response).await;
+            }
+        }
+    }
+}
+
+impl Authentication {
+    /// Add the authentication to a request
+    fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Authentication::Basic { username, password } => {
+                request.basic_auth(username, Some(password))
+            }
+            Authentication::ApiToken { username, token } => {
+                request.basic_auth(username, Some(token))
+            }
+            Authentication::Cookie(cookie) => request.header(reqwest::header::COOKIE, cookie),
+            Authentication::Header { name, value } => request.header(name.as_str(), value),
+            Authentication::Bearer(token) => request.bearer_auth(token),
         }
     }
 }
 
-impl Credentials {
-    /// Get username
-    pub fn get_username(&self) -> &str {
-        self.username.as_ref().map_or("", |s| s.as_str())
+/// Check whether a request was redirected to a login page, which Jenkins and
+/// SSO realms do instead of answering with 401 or 403
+fn is_login_redirect(requested: &str, response_url: &Url) -> bool {
+    let Ok(requested) = Url::parse(requested) else {
+        return false;
+    };
+    if requested.path() == response_url.path() && requested.origin() == response_url.origin() {
+        return false;
+    }
+    let path = response_url.path().to_lowercase();
+    requested.origin() != response_url.origin()
+        || path.ends_with("/login")
+        || path.contains("securityrealm/commencelogin")
+}
+
+/// Format a url for error messages, without credentials nor query, since
+/// tree queries make urls long and only the resource is relevant
+fn error_url(url: &str) -> String {
+    redact_url(url.split('?').next().unwrap_or(url))
+}
+
+/// Read a response along with its body as text
+async fn read_page(url: &str, response: Response) -> Result<Page> {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let response_url = response.url().clone();
+    let body = response.text().await.map_err(|source| Error::Http {
+        url: error_url(url),
+        source: source.without_url(),
+    })?;
+    Ok(Page {
+        status,
+        headers,
+        url: response_url,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Response of the test server: status, extra headers and body
+    type Reply = (u16, &'static str, String);
+
+    /// Start a server answering each request with `respond`, given the
+    /// request line and headers, and return its url
+    async fn serve<F>(respond: F) -> String
+    where
+        F: Fn(&str) -> Reply + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let respond = Arc::new(respond);
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let respond = respond.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 4096];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match stream.read(&mut buffer).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&buffer[..n]),
+                        }
+                    }
+                    let head = String::from_utf8_lossy(&request).to_lowercase();
+                    let (status, headers, body) = respond(&head);
+                    let response = format!(
+                        "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+                        status,
+                        body.len(),
+                        headers,
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        url
     }
 
-    /// Get password
-    pub fn get_password(&self) -> &str {
-        self.password.as_ref().map_or("", |s| s.as_str())
+    #[test]
+    fn rewrite_url_with_root_url() {
+        let mut client = JenkinsClient::builder("https://proxy/jenkins/")
+            .build()
+            .unwrap();
+        client.root_url = Some("http://internal:8080/".to_string());
+        assert_eq!(
+            client.rewrite_url("http://internal:8080/job/x/"),
+            "https://proxy/jenkins/job/x/"
+        );
+        // urls built by the client are kept, even under the root url
+        client.root_url = Some("https://proxy/".to_string());
+        assert_eq!(
+            client.rewrite_url("https://proxy/jenkins/api/json"),
+            "https://proxy/jenkins/api/json"
+        );
+    }
+
+    #[test]
+    fn rewrite_url_without_root_url() {
+        let client = JenkinsClient::builder("https://proxy/jenkins/")
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.rewrite_url("http://internal:8080/job/x/"),
+            "https://proxy/jenkins/job/x/"
+        );
+        assert_eq!(
+            client.rewrite_url("http://internal:8080/jenkins/job/x/"),
+            "https://proxy/jenkins/job/x/"
+        );
+        assert_eq!(
+            client.rewrite_url("https://proxy/jenkins/job/x/"),
+            "https://proxy/jenkins/job/x/"
+        );
+    }
+
+    #[tokio::test]
+    async fn post_refreshes_rejected_crumb() {
+        let crumbs = Arc::new(AtomicUsize::new(0));
+        let posts = Arc::new(AtomicUsize::new(0));
+        let (crumbs_served, posts_served) = (crumbs.clone(), posts.clone());
+        let url = serve(move |head| {
+            if head.starts_with("get /crumbissuer/api/json") {
+                let crumb = crumbs_served.fetch_add(1, Ordering::SeqCst) + 1;
+                let body = format!(
+                    r#"{{"crumbRequestField":"Jenkins-Crumb","crumb":"c{}"}}"#,
+                    crumb
+                );
+                return (200, "", body);
+            }
+            posts_served.fetch_add(1, Ordering::SeqCst);
+            if head.contains("jenkins-crumb: c2") {
+                (200, "", "ok".to_string())
+            } else {
+                (403, "", "No valid crumb was included".to_string())
+            }
+        })
+        .await;
+
+        let client = JenkinsClient::builder(url).build().unwrap();
+        assert_eq!(client.post_path("job/A/build").await.unwrap(), "ok");
+        assert_eq!(crumbs.load(Ordering::SeqCst), 2);
+        assert_eq!(posts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn throttled_post_is_not_replayed() {
+        let posts = Arc::new(AtomicUsize::new(0));
+        let posts_served = posts.clone();
+        let url = serve(move |head| {
+            if head.starts_with("get ") {
+                return (404, "", String::new());
+            }
+            posts_served.fetch_add(1, Ordering::SeqCst);
+            (429, "", String::new())
+        })
+        .await;
+
+        let client = JenkinsClient::builder(url).build().unwrap();
+        let error = client.post_path("job/A/build").await.unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Status {
+                status: StatusCode::TOO_MANY_REQUESTS,
+                ..
+            }
+        ));
+        assert_eq!(posts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn throttled_post_is_replayed_after_retry_after() {
+        let posts = Arc::new(AtomicUsize::new(0));
+        let posts_served = posts.clone();
+        let url = serve(move |head| {
+            if head.starts_with("get ") {
+                return (404, "", String::new());
+            }
+            match posts_served.fetch_add(1, Ordering::SeqCst) {
+                0 => (429, "Retry-After: 0\r\n", String::new()),
+                _ => (200, "", "ok".to_string()),
+            }
+        })
+        .await;
+
+        let client = JenkinsClient::builder(url).build().unwrap();
+        assert_eq!(client.post_path("job/A/build").await.unwrap(), "ok");
+        assert_eq!(posts.load(Ordering::SeqCst), 2);
     }
 }
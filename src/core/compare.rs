@@ -0,0 +1,159 @@
+use super::dump::Dumper;
+use super::models::{JobRecord, ViewRecord};
+use crate::error::Result;
+use crate::utils::create_directory;
+use futures::future::join_all;
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Name of the report inside the output directory
+pub const VISIBILITY_FILE: &str = "visibility.json";
+
+/// Endpoints checked for access, besides jobs, builds and views
+const ENDPOINTS: &[&str] = &[
+    "api/json",
+    "whoAmI/api/json",
+    "people/api/json",
+    "asynchPeople/api/json",
+    "computer/api/json",
+    "pluginManager/api/json",
+    "credentials/",
+    "manage/",
+    "systemInfo",
+    "log/",
+    "script",
+];
+
+/// Urls of everything visible during a discovery run
+#[derive(Debug, Default, Serialize)]
+pub struct Visibility {
+    pub jobs: BTreeSet<String>,
+    pub builds: BTreeSet<String>,
+    pub views: BTreeSet<String>,
+    /// Paths of the endpoints that could be read
+    pub endpoints: BTreeSet<String>,
+}
+
+impl Visibility {
+    /// Get what is visible here but not in the other run
+    pub fn difference(&self, other: &Visibility) -> Visibility {
+        Visibility {
+            jobs: self.jobs.difference(&other.jobs).cloned().collect(),
+            builds: self.builds.difference(&other.builds).cloned().collect(),
+            views: self.views.difference(&other.views).cloned().collect(),
+            endpoints: self
+                .endpoints
+                .difference(&other.endpoints)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+            && self.builds.is_empty()
+            && self.views.is_empty()
+            && self.endpoints.is_empty()
+    }
+
+    fn add_job(&mut self, job: &JobRecord) {
+        // jobs listed by their parent but not readable are not visible
+        if job.error.is_none() {
+            self.jobs.insert(job.url.clone());
+            self.builds.extend(job.builds.iter().flatten().cloned());
+        }
+        for sub_job in job.sub_jobs.iter().flatten() {
+            self.add_job(sub_job);
+        }
+    }
+
+    fn add_view(&mut self, view: &ViewRecord) {
+        self.views.insert(view.url.clone());
+        for sub_view in view.views.iter().flatten() {
+            self.add_view(sub_view);
+        }
+    }
+}
+
+/// Difference between what anonymous users and the given credentials can see
+#[derive(Debug, Serialize)]
+pub struct VisibilityReport {
+    pub url: String,
+    /// Visible without authentication
+    pub anonymous: Visibility,
+    /// Visible only with the credentials
+    pub authenticated_only: Visibility,
+    /// Visible without authentication but hidden from the credentials, which
+    /// happens with restricted accounts
+    pub anonymous_only: Visibility,
+}
+
+/// Run discovery with an anonymous dumper and an authenticated one and
+/// compare what each of them sees. The jobs and views of each run are dumped
+/// in the "anonymous" and "authenticated" directories under the output
+/// directory.
+pub async fn compare(
+    anonymous: &Dumper,
+    authenticated: &Dumper,
+    output_directory: &str,
+    last_only: bool,
+) -> Result<VisibilityReport> {
+    info!("Running discovery without credentials");
+    let anonymous_visibility = visibility(
+        anonymous,
+        &format!("{}/anonymous", output_directory),
+        last_only,
+    )
+    .await?;
+    info!("Running discovery with credentials");
+    let authenticated_visibility = visibility(
+        authenticated,
+        &format!("{}/authenticated", output_directory),
+        last_only,
+    )
+    .await?;
+
+    Ok(VisibilityReport {
+        url: authenticated.client.url().to_string(),
+        authenticated_only: authenticated_visibility.difference(&anonymous_visibility),
+        anonymous_only: anonymous_visibility.difference(&authenticated_visibility),
+        anonymous: anonymous_visibility,
+    })
+}
+
+/// Dump jobs and views with the given dumper and check which endpoints it
+/// can read. Being denied the job or view listing means nothing is visible.
+pub async fn visibility(
+    dumper: &Dumper,
+    output_directory: &str,
+    last_only: bool,
+) -> Result<Visibility> {
+    create_directory(output_directory)?;
+    let mut visibility = Visibility::default();
+
+    match dumper.dump_jobs(output_directory, last_only).await {
+        Ok(jobs) => jobs.iter().for_each(|job| visibility.add_job(job)),
+        Err(e) if e.is_auth() => warn!("Jobs are not visible: {}", e),
+        Err(e) => return Err(e),
+    }
+    match dumper.dump_views(output_directory).await {
+        Ok(views) => views.iter().for_each(|view| visibility.add_view(view)),
+        Err(e) if e.is_auth() => warn!("Views are not visible: {}", e),
+        Err(e) => return Err(e),
+    }
+
+    let tasks = ENDPOINTS.iter().map(|endpoint| async move {
+        let result = dumper.client.get_path(endpoint).await;
+        (endpoint, result)
+    });
+    for (endpoint, result) in join_all(tasks).await {
+        match result {
+            Ok(_) => {
+                visibility.endpoints.insert(endpoint.to_string());
+            }
+            Err(e) => debug!("Endpoint {} is not visible: {}", endpoint, e),
+        }
+    }
+    Ok(visibility)
+}
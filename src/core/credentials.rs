@@ -0,0 +1,276 @@
+use super::dump::{dumped_builds, Dumper};
+use super::models::{Build, CredentialDomain, CredentialRecord, CredentialUse, Item, Jenkins};
+use crate::error::Result;
+use crate::utils::{self, concatenate_url, encode_path_segment};
+use async_recursion::async_recursion;
+use futures::future::join_all;
+use log::{debug, info, warn};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Store holding the system level credentials
+const SYSTEM_STORE: &str = "credentials/store/system/";
+/// Store holding the credentials of a folder, under the folder url
+const FOLDER_STORE: &str = "credentials/store/folder/";
+/// Class of organization folders, which configure SCM sources like
+/// multibranch projects
+const ORGANIZATION_FOLDER_CLASS: &str = "jenkins.branch.OrganizationFolder";
+/// Fields of the credentials of a domain
+const CREDENTIALS_TREE: &str = "credentials[id,typeName,description,displayName,scope]";
+
+impl Dumper {
+    /// Dump the metadata of the credentials of the system store and of every
+    /// folder store to credentials.json. Each credential is linked to the
+    /// jobs whose config.xml, or whose dumped builds' environment variables,
+    /// reference its id. Multibranch projects and organization folders are
+    /// linked too, as their SCM sources reference credentials. Secret values
+    /// are never requested.
+    pub async fn dump_credentials(&self, output_directory: &str) -> Result<Vec<CredentialRecord>> {
+        let items = self.get_items_recursive(self.client.url()).await;
+        let folders: Vec<&Item> = items
+            .iter()
+            .filter(|item| !matches!(item, Item::Job(_)))
+            .collect();
+        debug!(
+            "Found {} items, {} of them folders",
+            items.len(),
+            folders.len()
+        );
+        let configured: Vec<&Item> = items.iter().filter(|item| is_configured(item)).collect();
+
+        let mut stores = vec![concatenate_url(self.client.url(), SYSTEM_STORE)?];
+        for folder in &folders {
+            stores.push(concatenate_url(
+                &self.client.rewrite_url(folder.url()),
+                FOLDER_STORE,
+            )?);
+        }
+        let tasks = stores.iter().map(|store| self.get_store_credentials(store));
+        let mut credentials: Vec<CredentialRecord> =
+            join_all(tasks).await.into_iter().flatten().collect();
+        info!(
+            "Found {} credentials in {} stores",
+            credentials.len(),
+            stores.len()
+        );
+
+        if !credentials.is_empty() {
+            let ids: Vec<&str> = credentials.iter().map(|c| c.id.as_str()).collect();
+            let patterns = id_patterns(&ids);
+            let mut uses = self.config_references(&configured, &patterns).await;
+            uses.extend(self.env_references(output_directory, &patterns)?);
+            for (index, credential_use) in uses {
+                credentials[index].jobs.push(credential_use);
+            }
+            for credential in &mut credentials {
+                credential.jobs.sort();
+                credential.jobs.dedup();
+            }
+        }
+
+        // Save credentials to file
+        let credentials_file = format!("{}/credentials.json", output_directory);
+        debug!("Saving credentials to {}", credentials_file);
+        utils::save_json(&credentials, &credentials_file)?;
+        Ok(credentials)
+    }
+
+    /// Get every item below the given url, folders and their descendants
+    /// included. Folders that cannot be read are skipped.
+    #[async_recursion]
+    async fn get_items_recursive(&self, url: &str) -> Vec<Item> {
+        let api_url = match concatenate_url(url, "api/json?tree=jobs[_class,name,url]") {
+            Ok(api_url) => api_url,
+            Err(e) => {
+                warn!("Error listing items of {}: {}", url, e);
+                return Vec::new();
+            }
+        };
+        let root: Jenkins = match self.client.get_json_url(&api_url).await {
+            Ok(root) => root,
+            Err(e) => {
+                warn!("Error listing items of {}: {}", url, e);
+                return Vec::new();
+            }
+        };
+        let tasks = root
+            .jobs
+            .iter()
+            .filter(|item| !matches!(item, Item::Job(_)))
+            .map(|folder| self.get_items_recursive(folder.url()));
+        let descendants: Vec<Vec<Item>> = join_all(tasks).await;
+        let mut items = root.jobs;
+        items.extend(descendants.into_iter().flatten());
+        items
+    }
+
+    /// Get the credentials of every domain of a store. A missing store,
+    /// like a folder without credentials, has no credentials.
+    async fn get_store_credentials(&self, store_url: &str) -> Vec<CredentialRecord> {
+        let domains = match self
+            .client
+            .get_json_url::<Value>(&format!("{}api/json?depth=1", store_url))
+            .await
+        {
+            Ok(store) => store
+                .get("domains")
+                .and_then(|domains| domains.as_object())
+                .map(|domains| domains.keys().cloned().collect())
+                .unwrap_or_default(),
+            Err(e) if e.is_not_found() => Vec::new(),
+            Err(e) => {
+                warn!("Error reading credential store {}: {}", store_url, e);
+                Vec::new()
+            }
+        };
+
+        let mut credentials = Vec::new();
+        for domain in domains {
+            let domain_url = format!(
+                "{}domain/{}/api/json?tree={}",
+                store_url,
+                encode_path_segment(&domain),
+                CREDENTIALS_TREE
+            );
+            match self
+                .client
+                .get_json_url::<CredentialDomain>(&domain_url)
+                .await
+            {
+                Ok(response) => {
+                    let response_credentials = response
+                        .credentials
+                        .into_iter()
+                        .filter(|credential| !credential.id.is_empty());
+                    credentials.extend(response_credentials.map(|credential| CredentialRecord {
+                        id: credential.id,
+                        type_name: credential.type_name,
+                        description: credential.description.filter(|d| !d.is_empty()),
+                        scope: credential.scope,
+                        domain: domain.clone(),
+                        store: store_url.to_string(),
+                        jobs: Vec::new(),
+                    }))
+                }
+                Err(e) => warn!("Error reading credential domain {}: {}", domain_url, e),
+            }
+        }
+        credentials
+    }
+
+    /// Find the credentials referenced by the configuration of each item,
+    /// which requires the extended read permission
+    async fn config_references(
+        &self,
+        items: &[&Item],
+        patterns: &[Regex],
+    ) -> Vec<(usize, CredentialUse)> {
+        let tasks = items.iter().map(|item| async move {
+            let job_url = self.client.rewrite_url(item.url());
+            let config = match concatenate_url(&job_url, "config.xml") {
+                Ok(config_url) => self.client.get_url(&config_url).await,
+                Err(e) => Err(e),
+            };
+            (job_url, config)
+        });
+        let mut uses = Vec::new();
+        let mut readable = 0;
+        for (job_url, config) in join_all(tasks).await {
+            let config = match config {
+                Ok(config) => config,
+                Err(e) => {
+                    debug!("Error reading configuration of {}: {}", job_url, e);
+                    continue;
+                }
+            };
+            readable += 1;
+            for index in referenced(&config, patterns) {
+                uses.push((
+                    index,
+                    CredentialUse {
+                        job: job_url.clone(),
+                        found_in: "config".to_string(),
+                    },
+                ));
+            }
+        }
+        info!(
+            "Read the configuration of {} out of {} jobs, multibranch projects and organization folders",
+            readable,
+            items.len()
+        );
+        uses
+    }
+
+    /// Find the credentials referenced by the environment variables of the
+    /// builds dumped in the output directory
+    fn env_references(
+        &self,
+        output_directory: &str,
+        patterns: &[Regex],
+    ) -> Result<Vec<(usize, CredentialUse)>> {
+        let mut uses = Vec::new();
+        for (build_directory, build) in dumped_builds::<Build>(output_directory)? {
+            let env_file = build_directory.join("injectedEnvVars.json");
+            let env_vars = match std::fs::read_to_string(&env_file) {
+                Ok(env_vars) => env_vars,
+                // the variables are only dumped with the EnvInject plugin
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    warn!(
+                        "Skipping unreadable environment variables {}: {}",
+                        env_file.to_string_lossy(),
+                        e
+                    );
+                    continue;
+                }
+            };
+            let indexes = referenced(&env_vars, patterns);
+            if indexes.is_empty() {
+                continue;
+            }
+            let Some(job_url) = build.job_url() else {
+                continue;
+            };
+            let job_url = self.client.rewrite_url(&job_url);
+            for index in indexes {
+                uses.push((
+                    index,
+                    CredentialUse {
+                        job: job_url.clone(),
+                        found_in: "env".to_string(),
+                    },
+                ));
+            }
+        }
+        Ok(uses)
+    }
+}
+
+/// Check whether the configuration of an item may reference credentials:
+/// jobs, and the SCM sources of multibranch projects and organization folders
+fn is_configured(item: &Item) -> bool {
+    match item {
+        Item::Job(_) | Item::WorkflowMultiBranchProject(_) => true,
+        Item::Folder(folder) => folder.class.as_deref() == Some(ORGANIZATION_FOLDER_CLASS),
+    }
+}
+
+/// Build a pattern matching each credential id as a whole word, so that
+/// short ids are not found inside longer names
+fn id_patterns(ids: &[&str]) -> Vec<Regex> {
+    ids.iter()
+        .map(|id| Regex::new(&format!(r"(?:^|[^\w.-]){}(?:$|[^\w.-])", regex::escape(id))).unwrap())
+        .collect()
+}
+
+/// Get the indexes of the credentials whose id appears in the text
+fn referenced(text: &str, patterns: &[Regex]) -> BTreeSet<usize> {
+    patterns
+        .iter()
+        .enumerate()
+        .filter(|(_, pattern)| pattern.is_match(text))
+        .map(|(index, _)| index)
+        .collect()
+}
@@ -1,43 +1,81 @@
 use super::client::JenkinsClient;
-use crate::utils::{self, concatenate_url, extract_path, search_substring};
+use super::models::{
+    Build, BuildError, BuildRef, DumpEntry, Item, Jenkins, Job, JobRecord, View, ViewRecord,
+};
+use super::progress::{BuildRecord, Progress};
+use super::secrets::FindingsReport;
+use crate::error::{AuthError, Error, Result};
 use crate::utils::create_directory;
+use crate::utils::{self, concatenate_url, extract_path};
 use async_recursion::async_recursion;
-use futures::future::{join_all, try_join_all};
+use async_stream::stream;
+use futures::future::join_all;
+use futures::stream::{self, FuturesUnordered, Stream, StreamExt};
 use log::{debug, info, warn};
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Number of times a failing build is retried
+const MAX_BUILD_RETRIES: u32 = 3;
+
+/// Number of builds requested per page when enumerating all builds of a job,
+/// which is also the size of the builds field
+const BUILDS_PAGE_SIZE: usize = 100;
+/// Number of folder levels fetched in a single job tree query
+const FOLDER_TREE_DEPTH: usize = 2;
+/// Class of the root object, which the primary view url points to
+const ROOT_CLASS: &str = "hudson.model.Hudson";
+/// Job fields used during discovery
+const JOB_FIELDS: &str =
+    "name,url,builds[url],lastSuccessfulBuild[url],lastCompletedBuild[url],lastStableBuild[url]";
 
 pub struct Dumper {
     pub client: JenkinsClient,
+    /// Maximum number of builds retrieved per job
+    max_builds: Option<usize>,
+}
+
+/// Outcome of a single build dump
+pub enum BuildStatus {
+    Dumped,
+    /// Already dumped in a previous run
+    Skipped,
+}
+
+/// Outcome of a builds dump
+#[derive(Default)]
+pub struct BuildsSummary {
+    pub succeeded: usize,
+    pub skipped: usize,
+    pub failed: usize,
 }
 
 // implement Dumper
 impl Dumper {
-    /// Create a new Dumper without credentials
-    pub fn new(url: String, verbose: bool, insecure: bool) -> Self {
+    /// Create a new Dumper using the given client
+    pub fn new(client: JenkinsClient) -> Self {
         Self {
-            client: JenkinsClient::new(url, verbose, insecure),
+            client,
+            max_builds: None,
         }
     }
 
-    /// Create a new Dumper with credentials
-    pub fn with_credentials(
-        url: String,
-        username: String,
-        password: String,
-        verbose: bool,
-        insecure: bool
-    ) -> Self {
-        Self {
-            client: JenkinsClient::with_credentials(url, username, password, verbose, insecure),
-        }
+    /// Detect the root of the Jenkins instance, so every path stays under its
+    /// context path
+    pub async fn detect_root(&mut self) -> Result<()> {
+        self.client.detect_root().await
+    }
+
+    /// Retrieve at most `max_builds` builds per job, the most recent first
+    pub fn max_builds(mut self, max_builds: Option<usize>) -> Self {
+        self.max_builds = max_builds;
+        self
     }
 
     /// Create output directory for build based on base directory and build
     /// path (e.g. "job/MyJob/1")
-    pub fn create_build_directory(
-        &self,
-        base_directory: &str,
-        build_path: &str,
-    ) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn create_build_directory(&self, base_directory: &str, build_path: &str) -> Result<String> {
         let build_directory = format!("{}/{}", base_directory, build_path);
         create_directory(&build_directory)?;
         Ok(build_directory)
@@ -48,164 +86,327 @@ impl Dumper {
         &self,
         output_directory: &str,
         last_only: bool,
-    ) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
-        let response = self.client.get_path("api/json").await?;
-
-        if search_substring(&response, r"Authentication required") {
-            return Err("Authentication required".into());
-        } else if search_substring(&response, r"Invalid password/token") {
-            return Err("Invalid password/token".into());
-        } else if search_substring(&response, r"missing the Overall/Read permission") {
-            return Err("Missing the Overall/Read permission".into());
-        } else {
-            let response: serde_json::Value = serde_json::from_str(&response)?;
-
-            // Process jobs concurrently using asynchronous tasks
-            let mut jobs_array = Vec::new();
-            if let Some(jobs) = response.get("jobs").and_then(|jobs| jobs.as_array()) {
-                debug!("Found {} jobs", jobs.len());
-                debug!("Retrieving job info recursively");
-
-                let tasks = jobs.iter().map(|job| async {
-                    if let Some(job_url) = job.get("url").and_then(|url| url.as_str()) {
-                        match self.get_jobs_recursive(job_url, last_only).await {
-                            Ok(job_info) => Some(job_info),
-                            Err(e) => {
-                                warn!("Error: {}", e);
-                                None
-                            }
-                        }
-                    } else {
-                        None
-                    }
-                });
+    ) -> Result<Vec<JobRecord>> {
+        let root_jobs = self.get_root_jobs().await?;
+
+        // Process jobs concurrently using asynchronous tasks
+        debug!("Found {} jobs", root_jobs.len());
+        debug!("Retrieving job info recursively");
+        let tasks = root_jobs
+            .iter()
+            .map(|job| self.get_job_or_error(job, last_only));
+
+        // Execute tasks concurrently and collect results
+        let results: Vec<Option<JobRecord>> = join_all(tasks).await;
+        let jobs: Vec<JobRecord> = results.into_iter().flatten().collect();
+
+        // Save jobs to file
+        let jobs_file = format!("{}/jobs.json", output_directory);
+        debug!("Saving jobs to {}", jobs_file);
+        utils::save_json(&jobs, &jobs_file)?;
+        Ok(jobs)
+    }
 
-                // Execute tasks concurrently and collect results
-                let results: Vec<Option<serde_json::Value>> = join_all(tasks).await;
-                jobs_array.extend(results.into_iter().filter_map(|result| result));
+    /// Stream every job of the tree as it is discovered, folders included.
+    /// Records are flat: sub-jobs are streamed on their own rather than
+    /// nested in `sub_jobs`. Jobs that cannot be retrieved are streamed with
+    /// their error, only a failure to list the top-level jobs ends the stream
+    /// with an error.
+    pub fn jobs(&self, last_only: bool) -> impl Stream<Item = Result<JobRecord>> + '_ {
+        stream! {
+            let root_jobs = match self.get_root_jobs().await {
+                Ok(root_jobs) => root_jobs,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            };
+            let mut tasks: FuturesUnordered<_> = root_jobs
+                .into_iter()
+                .map(|item| self.discover_job(item, last_only))
+                .collect();
+            while let Some((job_info, sub_jobs)) = tasks.next().await {
+                tasks.extend(
+                    sub_jobs
+                        .into_iter()
+                        .map(|item| self.discover_job(item, last_only)),
+                );
+                yield Ok(job_info);
             }
-            // Save jobs to file
-            let jobs_file = format!("{}/jobs.json", output_directory);
-            debug!("Saving jobs to {}", jobs_file);
-            utils::save_json(&serde_json::Value::Array(jobs_array.clone()), &jobs_file)?;
-            Ok(serde_json::Value::Array(jobs_array))
         }
     }
 
-    /// Get job information recursively
-    #[async_recursion(?Send)]
-    async fn get_jobs_recursive(
-        &self,
-        job_url: &str,
-        last_only: bool,
-    ) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
-        // Make a GET request to retrieve job information
-        debug!("Retrieving job info from: {}", job_url);
-        let response = self
-            .client
-            .get_url(format!("{}/api/json", job_url).as_str())
-            .await?;
+    /// Stream the builds of every job as they are retrieved. Builds that
+    /// cannot be retrieved are streamed as errors.
+    pub fn builds(&self, last_only: bool) -> impl Stream<Item = Result<Build>> + '_ {
+        stream! {
+            let jobs = self.jobs(last_only);
+            futures::pin_mut!(jobs);
+            while let Some(job) = jobs.next().await {
+                let job = match job {
+                    Ok(job) => job,
+                    Err(e) => {
+                        yield Err(e);
+                        continue;
+                    }
+                };
+                let tasks = job
+                    .builds
+                    .iter()
+                    .flatten()
+                    .map(|build_url| self.get_build(build_url));
+                for build in join_all(tasks).await {
+                    yield build;
+                }
+            }
+        }
+    }
 
-        // Parse the JSON response
-        let json: serde_json::Value = serde_json::from_str(&response)?;
+    /// Get the build information of the given build
+    pub async fn get_build(&self, build_url: &str) -> Result<Build> {
+        debug!("Retrieving build info from: {}", build_url);
+        self.client
+            .get_json_url(&concatenate_url(build_url, "api/json")?)
+            .await
+    }
 
-        // Create a JSON object to store job information
-        let mut job_info = serde_json::json!({
-            "name": json.get("name").and_then(|n| n.as_str()),
-            "url": json.get("url").and_then(|url| url.as_str()),
-        });
+    /// Get the top-level jobs. The top of the job tree is fetched in one
+    /// round trip, falling back to a shallow query if the server rejects the
+    /// deep one.
+    async fn get_root_jobs(&self) -> Result<Vec<Item>> {
+        let deep_path = format!("api/json?tree=jobs[{}]", job_tree(FOLDER_TREE_DEPTH));
+        let response: Jenkins = match self.client.get_json(&deep_path).await {
+            Ok(response) => response,
+            // authentication and permission problems are not caused by the
+            // query, nor solved by a simpler one
+            Err(e) if e.is_auth() => return Err(e),
+            Err(e) => {
+                debug!(
+                    "Deep jobs query failed ({}), falling back to shallow query",
+                    e
+                );
+                self.client.get_json("api/json?tree=jobs[name,url]").await?
+            }
+        };
+        Ok(response.jobs)
+    }
 
-        // If the job has sub-jobs, recursively process them concurrently
-        if let Some(sub_jobs) = json.get("jobs").and_then(|jobs| jobs.as_array()) {
-            let tasks = sub_jobs.iter().map(|sub_job| async {
-                if let Some(sub_job_url) = sub_job.get("url").and_then(|url| url.as_str()) {
-                    self.get_jobs_recursive(sub_job_url, last_only).await
-                } else {
-                    Ok(serde_json::Value::Null)
-                }
-            });
+    /// Get a job and the folder tree below it up to a few levels, only with
+    /// the fields used
+    async fn get_job_item(&self, job_url: &str) -> Result<Item> {
+        debug!("Retrieving job info from: {}", job_url);
+        let job_path = extract_path(job_url)?;
+        let deep_path = format!("api/json?tree={}", job_tree(FOLDER_TREE_DEPTH));
+        match self
+            .client
+            .get_json_url(&concatenate_url(job_url, &deep_path)?)
+            .await
+        {
+            Ok(item) => Ok(item),
+            Err(e) if e.is_auth() => Err(e.in_job(job_path)),
+            Err(e) => {
+                debug!(
+                    "Deep job query failed ({}), falling back to shallow query",
+                    e
+                );
+                let shallow_path = format!("api/json?tree={}", job_tree(0));
+                self.client
+                    .get_json_url(&concatenate_url(job_url, &shallow_path)?)
+                    .await
+                    .map_err(|e| e.in_job(job_path))
+            }
+        }
+    }
 
-            // Execute tasks concurrently and handle errors
-            let sub_jobs_info: Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> =
-                try_join_all(tasks).await;
-            match sub_jobs_info {
-                Ok(sub_jobs_info) => {
-                    job_info["sub_jobs"] = serde_json::Value::Array(sub_jobs_info);
-                }
-                Err(err) => {
-                    // Handle the error here, e.g., log it or return an error
-                    return Err(err);
+    /// Get job information recursively
+    async fn get_jobs_recursive(&self, job_url: &str, last_only: bool) -> Result<JobRecord> {
+        let item = self.get_job_item(job_url).await?;
+        Ok(self.get_job_info(&item, last_only).await)
+    }
+
+    /// Get the information of a single job tree item, retrieving it first if
+    /// it was only listed, along with its sub-jobs left to discover
+    async fn discover_job(&self, item: Item, last_only: bool) -> (JobRecord, Vec<Item>) {
+        let item = if item.is_expanded() || item.url().is_empty() {
+            item
+        } else {
+            match self.get_job_item(item.url()).await {
+                Ok(item) => item,
+                Err(e) => {
+                    warn!("Error retrieving job {}: {}", item.url(), e);
+                    let mut job_info = self.job_record(&item, last_only).await;
+                    job_info.error = Some(e.to_string());
+                    return (job_info, Vec::new());
                 }
             }
+        };
+        let sub_jobs = item.jobs().map(|jobs| jobs.to_vec()).unwrap_or_default();
+        (self.job_record(&item, last_only).await, sub_jobs)
+    }
+
+    /// Build job information from the job tree item, recursing into sub-jobs.
+    /// Sub-jobs already included by a deep query are processed without
+    /// further requests.
+    #[async_recursion]
+    async fn get_job_info(&self, item: &Item, last_only: bool) -> JobRecord {
+        let mut job_info = self.job_record(item, last_only).await;
+
+        // If the job has sub-jobs, recursively process them concurrently
+        if let Some(sub_jobs) = item.jobs() {
+            let tasks = sub_jobs
+                .iter()
+                .map(|sub_job| self.get_job_or_error(sub_job, last_only));
+
+            // Execute tasks concurrently, failed sub-jobs are kept with their
+            // error so siblings are not lost
+            let sub_jobs_info: Vec<Option<JobRecord>> = join_all(tasks).await;
+            job_info.sub_jobs = Some(sub_jobs_info.into_iter().flatten().collect());
         }
 
+        job_info
+    }
+
+    /// Build the information of a single job tree item, without its
+    /// sub-jobs
+    async fn job_record(&self, item: &Item, last_only: bool) -> JobRecord {
+        // Keep both the url reported by Jenkins and the one reachable from
+        // here
+        let mut job_info = JobRecord {
+            name: item.name().to_string(),
+            url: self.client.rewrite_url(item.url()),
+            original_url: item.url().to_string(),
+            ..Default::default()
+        };
+
         // If the job has builds, include their URLs
-        if let Some(builds) = json.get("builds").and_then(|builds| builds.as_array()) {
+        if let Item::Job(
+            job @ Job {
+                builds: Some(builds),
+                ..
+            },
+        ) = item
+        {
             if last_only {
-                // get the first valid url among lastSuccessfulBuild.url, or
-                // lastCompletedBuild.url, or lastStableBuild.url, or the first
-                // element from "builds", and add it
-                // to job_info as "builds" array
-                let mut build_urls = Vec::new();
-                if let Some(url) = json
-                    .get("lastSuccessfulBuild")
-                    .and_then(|build| build.get("url"))
-                    .and_then(|url| url.as_str())
-                {
-                    build_urls.push(serde_json::Value::String(url.to_string()));
-                } else if let Some(url) = json
-                    .get("lastCompletedBuild")
-                    .and_then(|build| build.get("url"))
-                    .and_then(|url| url.as_str())
-                {
-                    build_urls.push(serde_json::Value::String(url.to_string()));
-                } else if let Some(url) = json
-                    .get("lastStableBuild")
-                    .and_then(|build| build.get("url"))
-                    .and_then(|url| url.as_str())
-                {
-                    build_urls.push(serde_json::Value::String(url.to_string()));
-                } else if let Some(url) = builds
-                    .first()
-                    .and_then(|build| build.get("url"))
-                    .and_then(|url| url.as_str())
-                {
-                    build_urls.push(serde_json::Value::String(url.to_string()));
-                }
-                job_info["builds"] = serde_json::Value::Array(build_urls);
+                // get the first valid url among lastSuccessfulBuild, or
+                // lastCompletedBuild, or lastStableBuild, or the first
+                // element from "builds"
+                let last_build = job
+                    .last_successful_build
+                    .as_ref()
+                    .or(job.last_completed_build.as_ref())
+                    .or(job.last_stable_build.as_ref())
+                    .or(builds.first());
+                job_info.builds = Some(
+                    last_build
+                        .map(|build| self.client.rewrite_url(&build.url))
+                        .into_iter()
+                        .collect(),
+                );
             } else {
-                // get all build urls and add them to job_info as "builds" array
-                let build_urls: Vec<serde_json::Value> = builds
+                // The builds field only holds the 100 most recent builds, so
+                // all builds are paged through when it is full and more are
+                // wanted. It is used as is if paging fails.
+                let max_builds = self.max_builds.unwrap_or(usize::MAX);
+                let recent_builds: Vec<String> = builds
                     .iter()
-                    .filter_map(|build| build.get("url").and_then(|url| url.as_str()))
-                    .map(|url| serde_json::Value::String(url.to_string()))
+                    .take(max_builds)
+                    .map(|build| self.client.rewrite_url(&build.url))
                     .collect();
-                job_info["builds"] = serde_json::Value::Array(build_urls);
+                let build_urls = if builds.len() >= BUILDS_PAGE_SIZE && max_builds > builds.len() {
+                    match self.get_all_builds_urls(&job_info.url).await {
+                        Ok(build_urls) => build_urls,
+                        Err(e) => {
+                            warn!("Error retrieving all builds of {}: {}", job_info.url, e);
+                            recent_builds
+                        }
+                    }
+                } else {
+                    recent_builds
+                };
+                job_info.builds = Some(build_urls);
             }
         }
 
-        Ok(job_info)
+        job_info
+    }
+
+    /// Get the urls of every retained build of a job, paging through
+    /// allBuilds with range queries, up to the maximum number of builds
+    async fn get_all_builds_urls(&self, job_url: &str) -> Result<Vec<String>> {
+        let max_builds = self.max_builds.unwrap_or(usize::MAX);
+        let mut build_urls = Vec::new();
+        while build_urls.len() < max_builds {
+            let start = build_urls.len();
+            let end = (start + BUILDS_PAGE_SIZE).min(max_builds);
+            debug!("Retrieving builds {}-{} of {}", start, end, job_url);
+            let path = format!("api/json?tree=allBuilds[url]{{{},{}}}", start, end);
+            let job: Job = self
+                .client
+                .get_json_url(&concatenate_url(job_url, &path)?)
+                .await?;
+            let page: Vec<BuildRef> = job
+                .all_builds
+                .ok_or_else(|| Error::parse(job_url, "missing allBuilds field"))?;
+            build_urls.extend(page.iter().map(|build| self.client.rewrite_url(&build.url)));
+            // a short page is the last one
+            if page.len() < end - start {
+                break;
+            }
+        }
+        Ok(build_urls)
+    }
+
+    /// Get job information recursively from a job tree item, which may
+    /// already hold the job information from a deep query. If the job
+    /// cannot be retrieved, a record with the error is returned instead so
+    /// the failure is kept in the job tree.
+    async fn get_job_or_error(&self, item: &Item, last_only: bool) -> Option<JobRecord> {
+        // jobs and folders fully included by a deep query
+        if item.is_expanded() {
+            return Some(self.get_job_info(item, last_only).await);
+        }
+        let job_url = item.url();
+        if job_url.is_empty() {
+            return None;
+        }
+        match self.get_jobs_recursive(job_url, last_only).await {
+            Ok(job_info) => Some(job_info),
+            Err(e) => {
+                warn!("Error retrieving job {}: {}", job_url, e);
+                Some(JobRecord {
+                    name: item.name().to_string(),
+                    url: self.client.rewrite_url(job_url),
+                    original_url: job_url.to_string(),
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                })
+            }
+        }
     }
 
     /// Given a build url, dump consoleText and injectedEnvVars and save them
-    /// in a directory based on the build path (e.g. "job/MyJob/1")
-    pub async fn dump_build(
+    /// in a directory based on the build path (e.g. "job/MyJob/1"). Builds
+    /// already completed according to the progress manifest are skipped.
+    /// When a findings report is given, the dumped resources are scanned for
+    /// secrets as they are downloaded.
+    async fn dump_build(
         &self,
         build_url: &str,
         output_directory: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Make a GET request to retrieve build information
-        debug!("Retrieving build info from: {}", build_url);
-        let response = self
-            .client
-            .get_url(format!("{}/api/json", build_url).as_str())
-            .await?;
-
-        // Parse the JSON response
-        let build_info: serde_json::Value = serde_json::from_str(&response)?;
-
+        progress: &Progress,
+        findings: Option<&FindingsReport>,
+    ) -> Result<BuildStatus> {
         // Extract build path from url (e.g. "job/MyJob/1")
         let build_path = extract_path(build_url)?;
+        let build_directory = format!("{}/{}", output_directory, build_path);
+        if progress.is_complete(&build_path, &build_directory) {
+            info!("Skipping already dumped build: {}", build_path);
+            return Ok(BuildStatus::Skipped);
+        }
+
+        // Make a GET request to retrieve build information
+        let build_info = self.get_build(build_url).await?;
+
         info!("Dumping build: {}", build_path);
 
         let build_directory = self.create_build_directory(output_directory, &build_path)?;
@@ -213,19 +414,34 @@ impl Dumper {
         let build_info_file = format!("{}/build_info.json", build_directory);
         debug!("Saving build info to {}", build_info_file);
         utils::save_json(&build_info, &build_info_file)?;
+        let mut record = BuildRecord {
+            build_info: file_size(&build_info_file)?,
+            console_text: None,
+            injected_env_vars: None,
+            forbidden: Vec::new(),
+        };
 
         // Get /consoleText for the build
         debug!("Retrieving consoleText for build {}", build_path);
         let console_text_url = concatenate_url(build_url, "consoleText")?;
-        let console_text = self
-            .dump_console_text(&console_text_url)
-            .await
-            .unwrap_or_default();
+        let console_text = optional_resource(
+            self.dump_console_text(&console_text_url).await,
+            &build_path,
+            "consoleText",
+            &mut record,
+        )?
+        .unwrap_or_default();
         if !console_text.is_empty() {
             let console_text_file = format!("{}/consoleText", build_directory);
             debug!("Saving consoleText to {}", console_text_file);
+            record.console_text = Some(console_text.len() as u64);
+            if let Some(findings) = findings {
+                findings.scan(&console_text, &build_path, "consoleText")?;
+            }
             // async write to file
-            tokio::fs::write(console_text_file, console_text).await?;
+            tokio::fs::write(&console_text_file, console_text)
+                .await
+                .map_err(|e| Error::io(console_text_file, e))?;
         } else {
             debug!("consoleText is empty");
         }
@@ -233,18 +449,61 @@ impl Dumper {
         // Get /injectedEnvVars for the build
         debug!("Retrieving injectedEnvVars for build {}", build_path);
         let injected_env_vars_url = concatenate_url(build_url, "injectedEnvVars/api/json")?;
-        let injected_env_vars = self
-            .dump_injected_env_vars(&injected_env_vars_url)
-            .await
-            .unwrap_or_default();
+        let injected_env_vars = optional_resource(
+            self.dump_injected_env_vars(&injected_env_vars_url).await,
+            &build_path,
+            "injectedEnvVars",
+            &mut record,
+        )?
+        .unwrap_or_default();
         if !injected_env_vars.is_null() {
             let injected_env_vars_file = format!("{}/injectedEnvVars.json", build_directory);
             debug!("Saving injectedEnvVars to {}", injected_env_vars_file);
             utils::save_json(&injected_env_vars, &injected_env_vars_file)?;
+            record.injected_env_vars = Some(file_size(&injected_env_vars_file)?);
+            if let Some(findings) = findings {
+                // Scan the file as saved, so line numbers match
+                let text = serde_json::to_string_pretty(&injected_env_vars)
+                    .map_err(|e| Error::parse(&injected_env_vars_file, e))?;
+                findings.scan(&text, &build_path, "injectedEnvVars.json")?;
+            }
         } else {
             debug!("injectedEnvVars is empty");
         }
-        Ok(())
+
+        progress.record(&build_path, &record)?;
+        Ok(BuildStatus::Dumped)
+    }
+
+    /// Dump a build, retrying with exponential backoff when it fails
+    async fn dump_build_with_retries(
+        &self,
+        build_url: &str,
+        output_directory: &str,
+        progress: &Progress,
+        findings: Option<&FindingsReport>,
+    ) -> Result<BuildStatus> {
+        let mut attempt = 0;
+        loop {
+            match self
+                .dump_build(build_url, output_directory, progress, findings)
+                .await
+            {
+                Ok(status) => return Ok(status),
+                // only transient failures are retried, not missing or
+                // forbidden builds nor local errors
+                Err(e) if attempt >= MAX_BUILD_RETRIES || !e.is_retryable() => return Err(e),
+                Err(e) => {
+                    let delay = Duration::from_secs(1 << attempt);
+                    warn!(
+                        "Error dumping build {}: {}, retrying in {:?}",
+                        build_url, e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
     }
 
     /// Dump builds for all jobs
@@ -252,109 +511,240 @@ impl Dumper {
         &self,
         output_directory: &str,
         last_only: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        recover: bool,
+        scan: bool,
+    ) -> Result<BuildsSummary> {
         // get all jobs
         let jobs = self.dump_jobs(output_directory, last_only).await?;
         // if there are no jobs, return with an error
-        if let Some(jobs) = jobs.as_array() {
-            if jobs.is_empty() {
-                return Err("No jobs found".into());
-            } else {
-                debug!("Parsed {} jobs", jobs.len());
-            }
+        if jobs.is_empty() {
+            return Err(Error::Empty("jobs"));
         }
+        debug!("Parsed {} jobs", jobs.len());
         // get all builds urls
         let mut builds_urls = Vec::new();
-        let jobs = jobs.as_array().unwrap();
-        for job in jobs {
+        for job in &jobs {
             self.get_builds_urls_recursive(job, &mut builds_urls)
         }
 
-        // if there are no builds, return with an error
-        if builds_urls.is_empty() {
-            return Err("No builds found".into());
-        } else {
-            info!("Found {} builds", builds_urls.len());
-        }
-
-        // Create a semaphore with a limit of N parallel tasks
-        let semaphore = tokio::sync::Semaphore::new(20);
-
-        // Create tasks to dump builds concurrently
-        let tasks = builds_urls.iter().map(|build_url| {
-            let self_clone = self.clone();
-            async {
-                // Acquire a permit from the semaphore
-                let _permit = semaphore.acquire().await.unwrap();
-                self_clone.dump_build(build_url, output_directory).await
-            }
-        });
-
-        // Execute tasks concurrently and handle errors
-        let results: Result<Vec<_>, _> = try_join_all(tasks).await;
-        match results {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e),
-        }
+        self.dump_builds_urls(&builds_urls, output_directory, recover, scan)
+            .await
     }
 
-    /// Dump builds from a file
+    /// Dump builds from a file. The file can be a jobs dump, an errors.json
+    /// from a previous run, or a views dump, in which case the builds of the
+    /// member jobs are retrieved first.
     pub async fn dump_builds_from_file(
         &self,
         input_file: &str,
         output_directory: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        last_only: bool,
+        recover: bool,
+        scan: bool,
+    ) -> Result<BuildsSummary> {
         // get all jobs from file
-        let jobs = utils::load_json(input_file)?;
+        let entries: Vec<DumpEntry> = utils::load_json(input_file)?;
         // if there are no jobs, return with an error
-        if let Some(jobs) = jobs.as_array() {
-            if jobs.is_empty() {
-                return Err("No jobs found".into());
-            } else {
-                debug!("Parsed {} jobs", jobs.len());
-            }
+        if entries.is_empty() {
+            return Err(Error::Empty("jobs"));
         }
-        // get all builds urls
+        debug!("Parsed {} jobs", entries.len());
+        // get all builds urls, including failed builds from a previous
+        // errors.json
         let mut builds_urls = Vec::new();
-        let jobs = jobs.as_array().unwrap();
-        for job in jobs {
-            self.get_builds_urls_recursive(job, &mut builds_urls)
+        let mut views_jobs_urls = Vec::new();
+        for entry in &entries {
+            match entry {
+                DumpEntry::Job(job) => self.get_builds_urls_recursive(job, &mut builds_urls),
+                DumpEntry::Error(error) => builds_urls.push(error.build.clone()),
+                DumpEntry::View(view) => {
+                    self.get_views_jobs_urls_recursive(view, &mut views_jobs_urls)
+                }
+            }
         }
 
+        // resolve jobs referenced by views, if any
+        views_jobs_urls.sort();
+        views_jobs_urls.dedup();
+        if !views_jobs_urls.is_empty() {
+            debug!(
+                "Retrieving builds for {} jobs from views",
+                views_jobs_urls.len()
+            );
+            let tasks = views_jobs_urls.iter().map(|job_url| async {
+                match self.get_jobs_recursive(job_url, last_only).await {
+                    Ok(job_info) => Some(job_info),
+                    Err(e) => {
+                        warn!("Error: {}", e);
+                        None
+                    }
+                }
+            });
+            let results: Vec<Option<JobRecord>> = join_all(tasks).await;
+            for job in results.iter().flatten() {
+                self.get_builds_urls_recursive(job, &mut builds_urls);
+            }
+        }
+        // a job may belong to several views
+        builds_urls.sort();
+        builds_urls.dedup();
+
+        self.dump_builds_urls(&builds_urls, output_directory, recover, scan)
+            .await
+    }
+
+    /// Dump the given builds concurrently. A failing build does not stop the
+    /// others: failures are collected in errors.json, which can be given back
+    /// as a jobs file to retry them.
+    async fn dump_builds_urls(
+        &self,
+        builds_urls: &[String],
+        output_directory: &str,
+        recover: bool,
+        scan: bool,
+    ) -> Result<BuildsSummary> {
         // if there are no builds, return with an error
         if builds_urls.is_empty() {
-            return Err("No builds found".into());
+            return Err(Error::Empty("builds"));
         } else {
             info!("Found {} builds", builds_urls.len());
         }
 
-        // Create a semaphore with a limit of N parallel tasks
-        let semaphore = tokio::sync::Semaphore::new(20);
-
-        // Create tasks to dump builds concurrently
-        let tasks = builds_urls.iter().map(|build_url| {
-            let self_clone = self.clone();
-            async {
-                // Acquire a permit from the semaphore
-                let _permit = semaphore.acquire().await.unwrap();
+        let progress = Progress::open(output_directory, recover)?;
+        let findings = if scan {
+            Some(FindingsReport::open(output_directory, recover)?)
+        } else {
+            None
+        };
+
+        // Create tasks to dump builds concurrently, as many at a time as the
+        // client lets requests in flight, so that memory does not grow with
+        // the number of builds
+        let progress = &progress;
+        let findings = findings.as_ref();
+        // urls are cloned so that the tasks do not borrow from the iterator,
+        // which the compiler fails to prove Send for
+        let tasks = builds_urls.iter().cloned().map(|build_url| async move {
+            let result = self
+                .dump_build_with_retries(&build_url, output_directory, progress, findings)
+                .await;
+            (build_url, result)
+        });
 
-                self_clone.dump_build(build_url, output_directory).await
+        // Execute tasks concurrently and collect the outcome of each build
+        let results: Vec<_> = stream::iter(tasks)
+            .buffer_unordered(self.client.max_requests())
+            .collect()
+            .await;
+        let mut summary = BuildsSummary::default();
+        let mut errors = Vec::new();
+        for (build_url, result) in results {
+            match result {
+                Ok(BuildStatus::Dumped) => summary.succeeded += 1,
+                Ok(BuildStatus::Skipped) => summary.skipped += 1,
+                Err(e) => {
+                    warn!("Failed to dump build {}: {}", build_url, e);
+                    summary.failed += 1;
+                    errors.push(BuildError {
+                        build: build_url,
+                        error: e.to_string(),
+                    });
+                }
             }
-        });
+        }
+
+        // Save errors to file, in a stable order
+        errors.sort_by(|a, b| a.build.cmp(&b.build));
+        let errors_file = format!("{}/errors.json", output_directory);
+        debug!("Saving errors to {}", errors_file);
+        utils::save_json(&errors, &errors_file)?;
+        Ok(summary)
+    }
+
+    /// Dump all views, including nested views, mapping each one to the urls
+    /// of its member jobs
+    pub async fn dump_views(&self, output_directory: &str) -> Result<Vec<ViewRecord>> {
+        let response: Jenkins = self
+            .client
+            .get_json("api/json?tree=views[name,url]")
+            .await?;
+        debug!("Found {} views", response.views.len());
+        debug!("Retrieving view info recursively");
+
+        let tasks = response
+            .views
+            .iter()
+            .map(|view| self.get_view_or_none(view));
+
+        // Execute tasks concurrently and collect results
+        let results: Vec<Option<ViewRecord>> = join_all(tasks).await;
+        let views: Vec<ViewRecord> = results.into_iter().flatten().collect();
+
+        // Save views to file
+        let views_file = format!("{}/views.json", output_directory);
+        debug!("Saving views to {}", views_file);
+        utils::save_json(&views, &views_file)?;
+        Ok(views)
+    }
 
-        // Execute tasks concurrently and handle errors
-        let results: Result<Vec<_>, _> = try_join_all(tasks).await;
-        match results {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e),
+    /// Get view information recursively. Views from the Nested View plugin
+    /// expose their children in the "views" field.
+    #[async_recursion]
+    async fn get_views_recursive(&self, view_url: &str) -> Result<ViewRecord> {
+        debug!("Retrieving view info from: {}", view_url);
+        let view_api_url =
+            concatenate_url(view_url, "api/json?tree=name,url,jobs[url],views[url]")?;
+        let view: View = self.client.get_json_url(&view_api_url).await?;
+
+        let mut view_info = ViewRecord {
+            name: view.name.clone(),
+            url: self.client.rewrite_url(&view.url),
+            jobs: view
+                .jobs
+                .iter()
+                .flatten()
+                .map(|job| self.client.rewrite_url(job.url()))
+                .collect(),
+            views: None,
+        };
+
+        // If the view has nested views, recursively process them concurrently.
+        // The primary view is served from the root url, whose views are the
+        // top-level views themselves.
+        let is_root = view.class.as_deref() == Some(ROOT_CLASS);
+        if let (Some(sub_views), false) = (&view.views, is_root) {
+            let tasks = sub_views
+                .iter()
+                .map(|sub_view| self.get_view_or_none(sub_view));
+            let results: Vec<Option<ViewRecord>> = join_all(tasks).await;
+            view_info.views = Some(results.into_iter().flatten().collect());
+        }
+
+        Ok(view_info)
+    }
+
+    /// Get view information recursively from a listed view, logging the
+    /// error if it cannot be retrieved
+    async fn get_view_or_none(&self, view: &View) -> Option<ViewRecord> {
+        if view.url.is_empty() {
+            return None;
+        }
+        match self.get_views_recursive(&view.url).await {
+            // the root object answering for the primary view has no name
+            Ok(view_info) if view_info.name.is_empty() => Some(ViewRecord {
+                name: view.name.clone(),
+                ..view_info
+            }),
+            Ok(view_info) => Some(view_info),
+            Err(e) => {
+                warn!("Error: {}", e);
+                None
+            }
         }
     }
 
     /// Dump consoleText
-    async fn dump_console_text(
-        &self,
-        console_text_url: &str,
-    ) -> Result<String, Box<dyn std::error::Error>> {
+    async fn dump_console_text(&self, console_text_url: &str) -> Result<String> {
         // Make a GET request to retrieve consoleText
         debug!("Retrieving consoleText from: {}", console_text_url);
         let response = self.client.get_url(console_text_url).await?;
@@ -366,32 +756,99 @@ impl Dumper {
     async fn dump_injected_env_vars(
         &self,
         injected_env_vars_url: &str,
-    ) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    ) -> Result<serde_json::Value> {
         // Make a GET request to retrieve injectedEnvVars
         debug!("Retrieving injectedEnvVars from: {}", injected_env_vars_url);
-        let response = self.client.get_url(injected_env_vars_url).await?;
-        let json: serde_json::Value = serde_json::from_str(&response)?;
+        self.client.get_json_url(injected_env_vars_url).await
+    }
 
-        Ok(json)
+    /// Iterate over a views dump recursively and get the urls of member jobs
+    fn get_views_jobs_urls_recursive(&self, view: &ViewRecord, jobs_urls: &mut Vec<String>) {
+        jobs_urls.extend(view.jobs.iter().cloned());
+        for sub_view in view.views.iter().flatten() {
+            self.get_views_jobs_urls_recursive(sub_view, jobs_urls);
+        }
     }
 
-    /// Iterate over a serde_json::Value recursively and get builds urls,
-    /// returning a Vec<String> with all urls
-    fn get_builds_urls_recursive(&self, json: &serde_json::Value, builds_urls: &mut Vec<String>) {
-        if let Some(builds) = json.get("builds").and_then(|builds| builds.as_array()) {
-            // copy all elements from builds to builds_urls, where builds is an
-            // array of strings
-            builds_urls.extend(
-                builds
-                    .iter()
-                    .filter_map(|build| build.as_str())
-                    .map(|build| build.to_string()),
-            );
+    /// Iterate over a jobs dump recursively and get builds urls
+    fn get_builds_urls_recursive(&self, job: &JobRecord, builds_urls: &mut Vec<String>) {
+        builds_urls.extend(job.builds.iter().flatten().cloned());
+        for sub_job in job.sub_jobs.iter().flatten() {
+            self.get_builds_urls_recursive(sub_job, builds_urls);
+        }
+    }
+}
+
+/// Get the size of a file written during the dump
+fn file_size(filename: &str) -> Result<u64> {
+    std::fs::metadata(filename)
+        .map(|metadata| metadata.len())
+        .map_err(|e| Error::io(filename, e))
+}
+
+/// Get an optional resource of a build. A missing resource is skipped. A
+/// forbidden one is skipped too, but recorded so the build is dumped again on
+/// recovery, e.g. with better credentials. Any other error leaves the build
+/// incomplete.
+fn optional_resource<T>(
+    result: Result<T>,
+    build_path: &str,
+    resource: &str,
+    record: &mut BuildRecord,
+) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(
+            e @ Error::Auth {
+                kind: AuthError::MissingPermission { .. },
+                ..
+            },
+        ) => {
+            warn!("Skipping {} of {}: {}", resource, build_path, e);
+            record.forbidden.push(resource.to_string());
+            Ok(None)
         }
-        if let Some(sub_jobs) = json.get("sub_jobs").and_then(|jobs| jobs.as_array()) {
-            for sub_job in sub_jobs {
-                self.get_builds_urls_recursive(sub_job, builds_urls);
+        Err(e) => Err(e),
+    }
+}
+
+/// Build the tree expression for a job and the folder tree below it, up to
+/// the given number of levels. Jobs at the last level are only listed, to be
+/// retrieved by another query.
+fn job_tree(levels: usize) -> String {
+    if levels == 0 {
+        format!("{},jobs[name,url]", JOB_FIELDS)
+    } else {
+        format!("{},jobs[{}]", JOB_FIELDS, job_tree(levels - 1))
+    }
+}
+
+/// Load the builds dumped under the output directory, with the directory of
+/// each one. Builds that cannot be read, which a partial dump may leave, are
+/// skipped with a warning.
+pub fn dumped_builds<T: DeserializeOwned>(output_directory: &str) -> Result<Vec<(PathBuf, T)>> {
+    let mut builds = Vec::new();
+    for build_file in utils::find_files(output_directory, "build_info.json")? {
+        match utils::load_json(&build_file.to_string_lossy()) {
+            Ok(build) => {
+                let build_directory = build_file.parent().unwrap_or(&build_file).to_path_buf();
+                builds.push((build_directory, build));
             }
+            Err(e) => warn!("Skipping unreadable build: {}", e),
         }
     }
+    Ok(builds)
+}
+
+/// Compile-time check that the dump futures are `Send`, so they can be
+/// spawned on a multi-threaded runtime
+#[allow(dead_code)]
+fn assert_send(dumper: &Dumper) {
+    fn is_send<T: Send>(_: T) {}
+    is_send(dumper.dump_jobs("", false));
+    is_send(dumper.dump_builds("", false, false, false));
+    is_send(dumper.dump_builds_from_file("", "", false, false, false));
+    is_send(dumper.dump_views(""));
+    is_send(dumper.dump_credentials(""));
 }
@@ -0,0 +1,127 @@
+use super::advisories::{AdvisoryDatabase, CoreAdvisory, Version};
+use super::client::JenkinsClient;
+use crate::error::Result;
+use log::debug;
+use regex::Regex;
+use serde::Serialize;
+
+/// Name of the report inside the output directory
+pub const FINGERPRINT_FILE: &str = "fingerprint.json";
+
+/// Pages requested to find the version. Jenkins sets its headers on error
+/// pages too, so they are read even when access is denied.
+const PAGES: &[&str] = &["", "login", "api/json"];
+
+/// Header holding the version
+const VERSION_HEADER: &str = "X-Jenkins";
+/// Legacy header holding the version. Modern releases always send the fixed
+/// value "1.395" for compatibility, so it is trusted after the pages only.
+const LEGACY_VERSION_HEADER: &str = "X-Hudson";
+
+/// Patterns of the version in HTML pages: the root element attribute of
+/// recent releases, then the footer of older ones
+const VERSION_PATTERNS: &[&str] = &[
+    r#"data-version="([0-9][0-9.]*)""#,
+    r"Jenkins ver\. ([0-9][0-9.]*)",
+    r">Jenkins ([0-9]+\.[0-9.]*[0-9])<",
+];
+
+/// Version of the server and the advisories applying to it
+#[derive(Debug, Serialize)]
+pub struct Fingerprint {
+    pub url: String,
+    /// Version found, the X-Jenkins header taking precedence over pages, and
+    /// pages over the X-Hudson header
+    pub version: Option<String>,
+    pub lts: Option<bool>,
+    /// Value of the X-Jenkins-Session header, which changes on each restart
+    pub session: Option<String>,
+    /// Every place the version was found
+    pub sources: Vec<VersionSource>,
+    /// Date of the most recent advisory in the database used
+    pub advisories_updated: String,
+    pub advisories: Vec<CoreAdvisory>,
+}
+
+/// Version found in a header or page
+#[derive(Debug, Serialize)]
+pub struct VersionSource {
+    /// Where the version was found (e.g. "X-Jenkins header of login")
+    pub source: String,
+    pub version: String,
+}
+
+/// Find the version of the server and match it against the advisory database
+pub async fn fingerprint(
+    client: &JenkinsClient,
+    database: &AdvisoryDatabase,
+) -> Result<Fingerprint> {
+    let patterns: Vec<Regex> = VERSION_PATTERNS
+        .iter()
+        .map(|pattern| Regex::new(pattern).unwrap())
+        .collect();
+    let mut header_sources = Vec::new();
+    let mut page_sources = Vec::new();
+    let mut legacy_sources = Vec::new();
+    let mut session = None;
+
+    for path in PAGES {
+        let page_name = if path.is_empty() { "root page" } else { path };
+        let page = match client.get_page(path).await {
+            Ok(page) => page,
+            Err(e) => {
+                debug!("Error retrieving {}: {}", page_name, e);
+                continue;
+            }
+        };
+        for (header, sources) in [
+            (VERSION_HEADER, &mut header_sources),
+            (LEGACY_VERSION_HEADER, &mut legacy_sources),
+        ] {
+            if let Some(version) = page.header(header) {
+                sources.push(VersionSource {
+                    source: format!("{} header of {}", header, page_name),
+                    version: version.to_string(),
+                });
+            }
+        }
+        if session.is_none() {
+            session = page.header("X-Jenkins-Session").map(|s| s.to_string());
+        }
+        if let Some(version) = patterns
+            .iter()
+            .find_map(|pattern| pattern.captures(&page.body))
+            .map(|captures| captures[1].to_string())
+        {
+            page_sources.push(VersionSource {
+                source: format!("content of {}", page_name),
+                version,
+            });
+        }
+    }
+
+    let mut sources = header_sources;
+    sources.append(&mut page_sources);
+    sources.append(&mut legacy_sources);
+    let version = sources
+        .iter()
+        .find_map(|source| Version::parse(&source.version));
+    let advisories = match &version {
+        Some(version) => database
+            .core_advisories(version)
+            .into_iter()
+            .cloned()
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(Fingerprint {
+        url: client.url().to_string(),
+        lts: version.as_ref().map(|version| version.is_lts()),
+        version: version.map(|version| version.to_string()),
+        session,
+        sources,
+        advisories_updated: database.updated.clone(),
+        advisories,
+    })
+}
@@ -0,0 +1,99 @@
+use log::{debug, warn};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+
+/// Delay applied after the first throttled response
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+/// Upper bound for the adaptive backoff
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Backoff below which requests are no longer delayed
+const BACKOFF_FLOOR: Duration = Duration::from_millis(50);
+
+/// Limit the requests sent to the server: at most `max_requests` in flight,
+/// optionally spaced to a maximum rate, and slowed down adaptively when the
+/// server answers with 429 or 503
+pub struct Limiter {
+    max_requests: usize,
+    semaphore: Semaphore,
+    interval: Duration,
+    state: Mutex<LimiterState>,
+}
+
+struct LimiterState {
+    /// Earliest time the next request can be sent
+    next_slot: Instant,
+    /// Extra delay between requests while the server is throttling us
+    backoff: Duration,
+}
+
+impl Limiter {
+    /// Create a new Limiter. A rate of `None` means no rate limit.
+    pub fn new(max_requests: usize, requests_per_second: Option<f64>) -> Self {
+        let interval = requests_per_second
+            .filter(|rps| *rps > 0.0)
+            .map(|rps| Duration::from_secs_f64(1.0 / rps))
+            .unwrap_or_default();
+        let max_requests = max_requests.max(1);
+        Self {
+            max_requests,
+            semaphore: Semaphore::new(max_requests),
+            interval,
+            state: Mutex::new(LimiterState {
+                next_slot: Instant::now(),
+                backoff: Duration::ZERO,
+            }),
+        }
+    }
+
+    /// Maximum number of requests in flight
+    pub fn max_requests(&self) -> usize {
+        self.max_requests
+    }
+
+    /// Wait until a request can be sent. The request is in flight until the
+    /// returned permit is dropped.
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        let permit = self.semaphore.acquire().await.unwrap();
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            let now = Instant::now();
+            let slot = state.next_slot.max(now);
+            state.next_slot = slot + self.interval.max(state.backoff);
+            slot - now
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        permit
+    }
+
+    /// Slow down after a throttled response, honoring the Retry-After delay
+    /// when the server sent one
+    pub fn throttled(&self, retry_after: Option<Duration>) {
+        let mut state = self.state.lock().unwrap();
+        let backoff = (state.backoff * 2)
+            .max(MIN_BACKOFF)
+            .max(retry_after.unwrap_or_default())
+            .min(MAX_BACKOFF);
+        warn!(
+            "Server is throttling requests, backing off for {:?}",
+            backoff
+        );
+        state.backoff = backoff;
+        state.next_slot = state.next_slot.max(Instant::now() + backoff);
+    }
+
+    /// Speed up again after a successful response
+    pub fn succeeded(&self) {
+        let mut state = self.state.lock().unwrap();
+        if !state.backoff.is_zero() {
+            state.backoff /= 2;
+            if state.backoff < BACKOFF_FLOOR {
+                debug!("Server recovered, removing backoff");
+                state.backoff = Duration::ZERO;
+            }
+        }
+    }
+}
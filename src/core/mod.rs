@@ -1,2 +1,15 @@
-mod client;
+pub mod advisories;
+pub mod client;
+pub mod compare;
+pub mod credentials;
 pub mod dump;
+pub mod fingerprint;
+mod limiter;
+pub mod models;
+pub mod nodes;
+pub mod plugins;
+mod progress;
+pub mod scan;
+pub mod secrets;
+pub mod users;
+pub mod whoami;
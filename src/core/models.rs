@@ -0,0 +1,714 @@
+use super::advisories::PluginAdvisory;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+/// Classes of the items holding other jobs
+const FOLDER_CLASSES: [&str; 2] = [
+    "com.cloudbees.hudson.plugins.folder.Folder",
+    "jenkins.branch.OrganizationFolder",
+];
+const WORKFLOW_MULTI_BRANCH_PROJECT_CLASS: &str =
+    "org.jenkinsci.plugins.workflow.multibranch.WorkflowMultiBranchProject";
+const CAUSE_ACTION_CLASS: &str = "hudson.model.CauseAction";
+const PARAMETERS_ACTION_CLASS: &str = "hudson.model.ParametersAction";
+
+/// Root object of the API (e.g. "/api/json")
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Jenkins {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub jobs: Vec<Item>,
+    #[serde(default)]
+    pub views: Vec<View>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Item of the job tree, told apart by its `_class`. Items of unknown classes
+/// are folders if they hold jobs, and jobs otherwise.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum Item {
+    Folder(Folder),
+    WorkflowMultiBranchProject(WorkflowMultiBranchProject),
+    Job(Job),
+}
+
+/// Buildable job (e.g. freestyle project, pipeline, multibranch branch)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub url: String,
+    /// Most recent builds, at most 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builds: Option<Vec<BuildRef>>,
+    /// Every retained build, only returned when requested explicitly
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all_builds: Option<Vec<BuildRef>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_successful_build: Option<BuildRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_completed_build: Option<BuildRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_stable_build: Option<BuildRef>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Folder, including organization folders
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Folder {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub url: String,
+    /// Child items, missing when not requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<Vec<Item>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Multibranch pipeline, holding one job per branch or pull request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkflowMultiBranchProject {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub url: String,
+    /// Branch jobs, missing when not requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<Vec<Item>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Reference to a build, as listed by its job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildRef {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<u64>,
+    #[serde(default)]
+    pub url: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Build (run) of a job, as saved in build_info.json. Missing and null
+/// fields are both saved as null, so the file keeps a stable schema.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Build {
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    pub number: Option<u64>,
+    pub url: Option<String>,
+    pub full_display_name: Option<String>,
+    /// Result of the build, null while it is running
+    pub result: Option<String>,
+    pub building: Option<bool>,
+    /// Start time, in milliseconds since the epoch
+    pub timestamp: Option<i64>,
+    /// Duration, in milliseconds
+    pub duration: Option<i64>,
+    /// Name of the node the build ran on, empty for the built-in node
+    pub built_on: Option<String>,
+    #[serde(default)]
+    pub actions: Vec<Action>,
+    /// Changes built, for freestyle jobs
+    pub change_set: Option<ChangeSet>,
+    /// Changes built, for pipelines, one set per repository
+    #[serde(default)]
+    pub change_sets: Vec<ChangeSet>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Changes from a repository built by a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangeSet {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub items: Vec<ChangeSetItem>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Single change (e.g. a commit)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetItem {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<UserRef>,
+    /// Set by the Git plugin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_email: Option<String>,
+    /// Time of the change, in milliseconds since the epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// User referenced by another object, such as the author of a change
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserRef {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+}
+
+/// Action attached to a run. Actions other than causes and parameters are
+/// kept as is.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum Action {
+    Cause(CauseAction),
+    Parameters(ParametersAction),
+    Other(Value),
+}
+
+/// Causes that triggered a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CauseAction {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub causes: Vec<Cause>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Cause of a run (e.g. started by a user, by an upstream build, by SCM)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cause {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_description: Option<String>,
+    /// Set by UserIdCause
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+    /// Set by UpstreamCause
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream_project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream_build: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream_url: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Parameters a run was started with
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParametersAction {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub parameters: Vec<ParameterValue>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Value of a build parameter. Password parameters have no value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParameterValue {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// View, including nested views from the Nested View plugin
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct View {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub url: String,
+    /// Member jobs, missing when not requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<Vec<Item>>,
+    /// Nested views
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub views: Option<Vec<View>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Identity of the current request (e.g. "/whoAmI/api/json")
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WhoAmI {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub anonymous: bool,
+    #[serde(default)]
+    pub authenticated: bool,
+    /// Groups and roles granted to the identity
+    #[serde(default)]
+    pub authorities: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// User known to Jenkins (e.g. "/me/api/json", "/user/<id>/api/json")
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// User properties, including the email address from the Mailer plugin
+    #[serde(default)]
+    pub property: Vec<Value>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Users who contributed to the jobs (e.g. "/asynchPeople/api/json")
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct People {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub users: Vec<Person>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// User of the people listing, with the time of their last change
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    /// Time of the last change, in milliseconds since the epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_change: Option<i64>,
+    #[serde(default)]
+    pub user: User,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Nodes of the server (e.g. "/computer/api/json?depth=1")
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ComputerSet {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub computer: Vec<Computer>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Node running builds: the built-in node or an agent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Computer {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub assigned_labels: Vec<Label>,
+    /// Data of the node monitors, by monitor class
+    #[serde(default)]
+    pub monitor_data: Map<String, Value>,
+    #[serde(default)]
+    pub offline: bool,
+    /// Whether the agent connects to the controller (inbound agent)
+    #[serde(default)]
+    pub jnlp_agent: bool,
+    #[serde(default)]
+    pub num_executors: u64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Label assigned to a node
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Label {
+    #[serde(default)]
+    pub name: String,
+}
+
+/// Credentials of a domain of a credential store (e.g.
+/// "/credentials/store/system/domain/_/api/json")
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialDomain {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub credentials: Vec<Credential>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Metadata of a credential, the API never returns its secret
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Credential {
+    #[serde(default)]
+    pub id: String,
+    /// Kind of credential (e.g. "Username with password")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Plugin manager, listing the installed plugins (e.g.
+/// "/pluginManager/api/json?depth=1")
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginManager {
+    #[serde(rename = "_class", skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub plugins: Vec<Plugin>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Installed plugin
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Plugin {
+    #[serde(default)]
+    pub short_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_name: Option<String>,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub dependencies: Vec<PluginDependency>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Plugin required by another plugin, with the minimum version required
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginDependency {
+    #[serde(default)]
+    pub short_name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub optional: bool,
+}
+
+/// Job as saved in jobs.json, with the urls of the builds to dump
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobRecord {
+    #[serde(default)]
+    pub name: String,
+    /// Url reachable from here
+    #[serde(default)]
+    pub url: String,
+    /// Url reported by Jenkins, which may be under another root url
+    #[serde(default)]
+    pub original_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_jobs: Option<Vec<JobRecord>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builds: Option<Vec<String>>,
+    /// Why the job could not be retrieved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// View as saved in views.json, with the urls of its member jobs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ViewRecord {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub url: String,
+    pub jobs: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub views: Option<Vec<ViewRecord>>,
+}
+
+/// Failed build as saved in errors.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildError {
+    pub build: String,
+    pub error: String,
+}
+
+/// Plugin as saved in plugins.json, with the advisories affecting it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginRecord {
+    pub short_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_name: Option<String>,
+    /// Installed version, unknown for inferred plugins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<PluginDependency>,
+    /// Classes the plugin was inferred from, when plugins could not be listed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inferred_from: Vec<String>,
+    /// Advisories affecting the installed version, or any version if it is
+    /// unknown
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub advisories: Vec<PluginAdvisory>,
+}
+
+/// User as saved in users.json, merged from every place it was seen
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserRecord {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Time of the last change or build of the user, in milliseconds since
+    /// the epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_activity: Option<i64>,
+    /// Where the user was seen (e.g. "asynchPeople", "cause", "changeSet")
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+/// Node as saved in nodes.json, with the jobs whose builds ran on it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeRecord {
+    pub name: String,
+    /// Whether this is the built-in node, running builds on the controller
+    pub built_in: bool,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+    pub online: bool,
+    /// Launcher class (e.g. "hudson.plugins.sshslaves.SSHLauncher"), or
+    /// "inbound" when only known to connect by itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launcher: Option<String>,
+    pub executors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Jobs with dumped builds that ran on the node
+    #[serde(default)]
+    pub jobs: Vec<String>,
+}
+
+/// Credential as saved in credentials.json, with the jobs referencing it.
+/// Only metadata is recorded, never secret values.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialRecord {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Scope (e.g. "GLOBAL", "SYSTEM"), when exposed by the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Domain name, "_" being the global domain
+    pub domain: String,
+    /// Url of the store holding the credential, at system level or in a
+    /// folder
+    pub store: String,
+    #[serde(default)]
+    pub jobs: Vec<CredentialUse>,
+}
+
+/// Job referencing a credential id
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CredentialUse {
+    pub job: String,
+    /// Where the id was found: "config" for config.xml, "env" for the
+    /// environment variables of a dumped build
+    pub found_in: String,
+}
+
+/// Entry of a dump file given back to the builds dump: a job from jobs.json,
+/// a view from views.json or a failed build from errors.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DumpEntry {
+    Error(BuildError),
+    View(ViewRecord),
+    Job(JobRecord),
+}
+
+impl Build {
+    /// Get the url of the job the build belongs to (e.g.
+    /// "https://host/job/MyJob/1/" -> "https://host/job/MyJob/")
+    pub fn job_url(&self) -> Option<String> {
+        let url = self.url.as_deref()?.trim_end_matches('/');
+        let (job_url, _) = url.rsplit_once('/')?;
+        Some(format!("{}/", job_url))
+    }
+}
+
+impl User {
+    /// Get the email address set by the Mailer plugin, if exposed
+    pub fn email(&self) -> Option<&str> {
+        self.property
+            .iter()
+            .find(|property| {
+                property
+                    .get("_class")
+                    .and_then(|class| class.as_str())
+                    .is_some_and(|class| class.ends_with("Mailer$UserProperty"))
+            })
+            .and_then(|property| property.get("address"))
+            .and_then(|address| address.as_str())
+            .filter(|address| !address.is_empty())
+    }
+}
+
+impl Item {
+    pub fn name(&self) -> &str {
+        match self {
+            Item::Folder(folder) => &folder.name,
+            Item::WorkflowMultiBranchProject(project) => &project.name,
+            Item::Job(job) => &job.name,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match self {
+            Item::Folder(folder) => &folder.url,
+            Item::WorkflowMultiBranchProject(project) => &project.url,
+            Item::Job(job) => &job.url,
+        }
+    }
+
+    /// Child items of folders and multibranch projects
+    pub fn jobs(&self) -> Option<&[Item]> {
+        match self {
+            Item::Folder(folder) => folder.jobs.as_deref(),
+            Item::WorkflowMultiBranchProject(project) => project.jobs.as_deref(),
+            Item::Job(_) => None,
+        }
+    }
+
+    /// Check whether the item was returned with its builds or child items,
+    /// rather than only listed
+    pub fn is_expanded(&self) -> bool {
+        match self {
+            Item::Job(job) => job.builds.is_some(),
+            item => item.jobs().is_some(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Item {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let class = value.get("_class").and_then(|class| class.as_str());
+        let item = match class {
+            Some(WORKFLOW_MULTI_BRANCH_PROJECT_CLASS) => {
+                serde_json::from_value(value).map(Item::WorkflowMultiBranchProject)
+            }
+            Some(class) if FOLDER_CLASSES.contains(&class) => {
+                serde_json::from_value(value).map(Item::Folder)
+            }
+            // unknown classes, e.g. from plugins
+            _ if value.get("jobs").is_some() => serde_json::from_value(value).map(Item::Folder),
+            _ => serde_json::from_value(value).map(Item::Job),
+        };
+        item.map_err(serde::de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let class = value.get("_class").and_then(|class| class.as_str());
+        let action = match class {
+            Some(CAUSE_ACTION_CLASS) => serde_json::from_value(value).map(Action::Cause),
+            Some(PARAMETERS_ACTION_CLASS) => serde_json::from_value(value).map(Action::Parameters),
+            // subclasses, e.g. from plugins
+            _ if value.get("causes").is_some() => serde_json::from_value(value).map(Action::Cause),
+            _ if value.get("parameters").is_some() => {
+                serde_json::from_value(value).map(Action::Parameters)
+            }
+            _ => Ok(Action::Other(value)),
+        };
+        action.map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_keeps_nulls() {
+        let json = r#"{"_class":"hudson.model.FreeStyleBuild","number":2,"result":null,"building":true,"builtOn":"","actions":[{"_class":"hudson.model.CauseAction","causes":[]},{}],"description":null}"#;
+        let build: Build = serde_json::from_str(json).unwrap();
+        let saved = serde_json::to_value(&build).unwrap();
+        assert_eq!(saved["result"], Value::Null);
+        assert_eq!(saved["description"], Value::Null);
+        assert_eq!(saved["builtOn"], "");
+        assert_eq!(saved["actions"].as_array().unwrap().len(), 2);
+        let reloaded: Build = serde_json::from_value(saved.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&reloaded).unwrap(), saved);
+    }
+}
@@ -0,0 +1,143 @@
+use super::dump::{dumped_builds, Dumper};
+use super::models::{Build, Computer, ComputerSet, NodeRecord};
+use crate::error::Result;
+use crate::utils::{self, encode_path_segment};
+use futures::future::join_all;
+use log::{debug, info};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Class of the built-in node, which runs builds on the controller itself
+const BUILT_IN_CLASS: &str = "hudson.model.Hudson$MasterComputer";
+/// Monitor reporting the OS and architecture (e.g. "Linux (amd64)")
+const ARCHITECTURE_MONITOR: &str = "hudson.node_monitors.ArchitectureMonitor";
+/// Fields of the nodes
+const COMPUTER_TREE: &str = "computer[_class,displayName,description,assignedLabels[name],\
+     monitorData[*],offline,jnlpAgent,numExecutors]";
+
+impl Dumper {
+    /// Dump the nodes to nodes.json, with the jobs whose builds, already
+    /// dumped in the output directory, ran on each node. The launcher is read
+    /// from the node configuration, which requires the extended read
+    /// permission.
+    pub async fn dump_nodes(&self, output_directory: &str) -> Result<Vec<NodeRecord>> {
+        let computers: ComputerSet = self
+            .client
+            .get_json(&format!("computer/api/json?tree={}", COMPUTER_TREE))
+            .await?;
+        debug!("Found {} nodes", computers.computer.len());
+
+        let launcher = Regex::new(r#"<launcher class="([^"]+)""#).unwrap();
+        let tasks = computers
+            .computer
+            .iter()
+            .map(|computer| self.node_record(computer, &launcher));
+        let mut nodes: Vec<NodeRecord> = join_all(tasks).await;
+
+        // Map the builtOn of the dumped builds to the jobs: the built-in node
+        // is reported with an empty name
+        let builds = dumped_builds::<Build>(output_directory)?;
+        if builds.is_empty() {
+            info!(
+                "No builds dumped in {}, jobs are not mapped to nodes",
+                output_directory
+            );
+        }
+        let mut node_jobs: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for (_, build) in builds {
+            if let (Some(built_on), Some(job_url)) = (&build.built_on, build.job_url()) {
+                node_jobs
+                    .entry(built_on.clone())
+                    .or_default()
+                    .insert(self.client.rewrite_url(&job_url));
+            }
+        }
+        for node in &mut nodes {
+            let name = if node.built_in {
+                ""
+            } else {
+                node.name.as_str()
+            };
+            if let Some(jobs) = node_jobs.remove(name) {
+                node.jobs = jobs.into_iter().collect();
+            }
+        }
+
+        // Save nodes to file
+        let nodes_file = format!("{}/nodes.json", output_directory);
+        debug!("Saving nodes to {}", nodes_file);
+        utils::save_json(&nodes, &nodes_file)?;
+        Ok(nodes)
+    }
+
+    /// Build the record of a node, reading its launcher from its
+    /// configuration when allowed
+    async fn node_record(&self, computer: &Computer, launcher: &Regex) -> NodeRecord {
+        let built_in = computer.class.as_deref() == Some(BUILT_IN_CLASS);
+        let (os, arch) = computer
+            .monitor_data
+            .get(ARCHITECTURE_MONITOR)
+            .and_then(|architecture| architecture.as_str())
+            .map(parse_architecture)
+            .unwrap_or_default();
+        let launcher = if built_in {
+            None
+        } else {
+            match self.get_launcher(&computer.display_name, launcher).await {
+                Ok(Some(launcher)) => Some(launcher),
+                Ok(None) | Err(_) if computer.jnlp_agent => Some("inbound".to_string()),
+                Ok(None) => None,
+                Err(e) => {
+                    debug!(
+                        "Error reading the launcher of {}: {}",
+                        computer.display_name, e
+                    );
+                    None
+                }
+            }
+        };
+        NodeRecord {
+            name: computer.display_name.clone(),
+            built_in,
+            labels: computer
+                .assigned_labels
+                .iter()
+                .map(|label| label.name.clone())
+                .collect(),
+            os,
+            arch,
+            online: !computer.offline,
+            launcher,
+            executors: computer.num_executors,
+            description: computer.description.clone().filter(|d| !d.is_empty()),
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Get the launcher class from the configuration of an agent, with the
+    /// given pattern capturing it
+    async fn get_launcher(&self, name: &str, launcher: &Regex) -> Result<Option<String>> {
+        let config = self
+            .client
+            .get_path(&format!(
+                "computer/{}/config.xml",
+                encode_path_segment(name)
+            ))
+            .await?;
+        Ok(launcher
+            .captures(&config)
+            .map(|captures| captures[1].to_string()))
+    }
+}
+
+/// Split the architecture reported by the monitor (e.g. "Linux (amd64)") into
+/// the OS and the architecture
+fn parse_architecture(architecture: &str) -> (Option<String>, Option<String>) {
+    match architecture.rsplit_once(" (") {
+        Some((os, arch)) => (
+            Some(os.to_string()),
+            Some(arch.trim_end_matches(')').to_string()),
+        ),
+        None => (Some(architecture.to_string()), None),
+    }
+}
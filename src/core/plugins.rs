@@ -0,0 +1,210 @@
+use super::advisories::AdvisoryDatabase;
+use super::dump::{dumped_builds, Dumper};
+use super::models::{PluginManager, PluginRecord};
+use crate::error::Result;
+use crate::utils;
+use log::{debug, info, warn};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Number of folder levels whose classes are collected when inferring
+/// plugins
+const CLASS_TREE_DEPTH: usize = 3;
+
+/// Packages, or classes, defined by plugins, used to infer the installed
+/// plugins from the classes seen in the API
+const CLASS_PLUGINS: &[(&str, &str)] = &[
+    ("com.cloudbees.hudson.plugins.folder", "cloudbees-folder"),
+    (
+        "com.cloudbees.jenkins.plugins.bitbucket",
+        "cloudbees-bitbucket-branch-source",
+    ),
+    ("com.cloudbees.jenkins.GitHubPushCause", "github"),
+    ("com.cloudbees.plugins.credentials", "credentials"),
+    ("com.dabsquared.gitlabjenkins", "gitlab-plugin"),
+    ("hudson.matrix", "matrix-project"),
+    ("hudson.maven", "maven-plugin"),
+    ("hudson.plugins.ansicolor", "ansicolor"),
+    ("hudson.plugins.copyartifact", "copyartifact"),
+    ("hudson.plugins.emailext", "email-ext"),
+    ("hudson.plugins.git", "git"),
+    ("hudson.plugins.gradle", "gradle"),
+    (
+        "hudson.plugins.parameterizedtrigger",
+        "parameterized-trigger",
+    ),
+    ("hudson.plugins.promoted_builds", "promoted-builds"),
+    ("hudson.plugins.timestamper", "timestamper"),
+    ("hudson.plugins.ws_cleanup", "ws-cleanup"),
+    ("hudson.tasks.junit", "junit"),
+    ("io.jenkins.blueocean", "blueocean"),
+    ("jenkins.branch", "branch-api"),
+    ("jenkins.plugins.git", "git"),
+    ("jenkins.plugins.slack", "slack"),
+    ("jenkins.scm.api", "scm-api"),
+    ("org.biouno.unochoice", "uno-choice"),
+    ("org.csanchez.jenkins.plugins.kubernetes", "kubernetes"),
+    ("org.jenkinsci.plugins.configfiles", "config-file-provider"),
+    (
+        "org.jenkinsci.plugins.credentialsbinding",
+        "credentials-binding",
+    ),
+    ("org.jenkinsci.plugins.docker.workflow", "docker-workflow"),
+    ("org.jenkinsci.plugins.envinject", "envinject"),
+    ("org.jenkinsci.plugins.ghprb", "ghprb"),
+    (
+        "org.jenkinsci.plugins.github_branch_source",
+        "github-branch-source",
+    ),
+    ("org.jenkinsci.plugins.gitclient", "git-client"),
+    (
+        "org.jenkinsci.plugins.pipeline.modeldefinition",
+        "pipeline-model-definition",
+    ),
+    ("org.jenkinsci.plugins.scriptsecurity", "script-security"),
+    ("org.jenkinsci.plugins.workflow.cps", "workflow-cps"),
+    ("org.jenkinsci.plugins.workflow.job", "workflow-job"),
+    ("org.jenkinsci.plugins.workflow.libs", "pipeline-groovy-lib"),
+    (
+        "org.jenkinsci.plugins.workflow.multibranch",
+        "workflow-multibranch",
+    ),
+    ("org.jenkinsci.plugins.workflow.support", "workflow-support"),
+    (
+        "org.jvnet.hudson.plugins.groovypostbuild",
+        "groovy-postbuild",
+    ),
+];
+
+impl Dumper {
+    /// Dump the installed plugins to plugins.json, with the advisories
+    /// affecting them. When plugins cannot be listed, they are inferred from
+    /// the classes of the jobs, and of the builds already dumped in the
+    /// output directory.
+    pub async fn dump_plugins(
+        &self,
+        output_directory: &str,
+        database: &AdvisoryDatabase,
+    ) -> Result<Vec<PluginRecord>> {
+        let mut plugins = match self
+            .client
+            .get_json::<PluginManager>("pluginManager/api/json?depth=1")
+            .await
+        {
+            Ok(manager) => manager
+                .plugins
+                .into_iter()
+                .map(|plugin| PluginRecord {
+                    short_name: plugin.short_name,
+                    long_name: plugin.long_name,
+                    version: Some(plugin.version),
+                    enabled: Some(plugin.enabled),
+                    active: Some(plugin.active),
+                    dependencies: plugin.dependencies,
+                    ..Default::default()
+                })
+                .collect(),
+            Err(e) if e.is_auth() => {
+                warn!("Cannot list plugins ({}), inferring them from classes", e);
+                self.infer_plugins(output_directory).await?
+            }
+            Err(e) => return Err(e),
+        };
+        debug!("Found {} plugins", plugins.len());
+
+        for plugin in &mut plugins {
+            plugin.advisories = database
+                .plugin_advisories(&plugin.short_name, plugin.version.as_deref())
+                .into_iter()
+                .cloned()
+                .collect();
+        }
+        plugins.sort_by(|a, b| a.short_name.cmp(&b.short_name));
+
+        // Save plugins to file
+        let plugins_file = format!("{}/plugins.json", output_directory);
+        debug!("Saving plugins to {}", plugins_file);
+        utils::save_json(&plugins, &plugins_file)?;
+        Ok(plugins)
+    }
+
+    /// Infer the installed plugins from the classes of the job tree, of the
+    /// last build of each job, and of the builds already dumped
+    async fn infer_plugins(&self, output_directory: &str) -> Result<Vec<PluginRecord>> {
+        let mut classes = BTreeSet::new();
+        let tree = format!("api/json?tree={}", class_tree(CLASS_TREE_DEPTH));
+        match self.client.get_json::<Value>(&tree).await {
+            Ok(root) => collect_classes(&root, &mut classes),
+            Err(e) => warn!("Error retrieving job classes: {}", e),
+        }
+        // builds are read as plain JSON, to find classes in every field
+        for (_, build) in dumped_builds::<Value>(output_directory)? {
+            collect_classes(&build, &mut classes);
+        }
+        info!("Inferring plugins from {} classes", classes.len());
+
+        let mut plugins: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for class in classes {
+            if let Some(plugin) = class_plugin(&class) {
+                plugins.entry(plugin).or_default().push(class);
+            }
+        }
+        Ok(plugins
+            .into_iter()
+            .map(|(short_name, classes)| PluginRecord {
+                short_name: short_name.to_string(),
+                inferred_from: classes,
+                ..Default::default()
+            })
+            .collect())
+    }
+}
+
+/// Build the tree expression for the classes of the jobs, of their last
+/// build and its actions, and of the folder tree below them, up to the given
+/// number of levels
+fn class_tree(levels: usize) -> String {
+    let fields = "_class,lastBuild[_class,actions[_class]]";
+    if levels == 0 {
+        format!("_class,jobs[{}]", fields)
+    } else {
+        let sub_jobs = class_tree(levels - 1);
+        format!(
+            "_class,jobs[{},{}]",
+            fields,
+            sub_jobs.trim_start_matches("_class,")
+        )
+    }
+}
+
+/// Collect every `_class` value of a JSON document
+fn collect_classes(value: &Value, classes: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(class)) = object.get("_class") {
+                classes.insert(class.clone());
+            }
+            object
+                .values()
+                .for_each(|value| collect_classes(value, classes));
+        }
+        Value::Array(array) => array
+            .iter()
+            .for_each(|value| collect_classes(value, classes)),
+        _ => {}
+    }
+}
+
+/// Get the plugin defining the given class, from the longest matching
+/// package
+fn class_plugin(class: &str) -> Option<&'static str> {
+    CLASS_PLUGINS
+        .iter()
+        .filter(|(package, _)| {
+            class
+                .strip_prefix(package)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '$']))
+        })
+        .max_by_key(|(package, _)| package.len())
+        .map(|(_, plugin)| *plugin)
+}
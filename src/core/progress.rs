@@ -0,0 +1,152 @@
+use crate::error::{Error, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::sync::Mutex;
+
+/// Name of the progress manifest inside the output directory
+const PROGRESS_FILE: &str = "progress.jsonl";
+
+/// Persisted progress of a builds dump. Every completed build is appended as
+/// a JSON line to the manifest, along with the sizes of the files written, so
+/// an interrupted dump can be resumed and partial builds detected.
+pub struct Progress {
+    file: Mutex<File>,
+    completed: HashMap<String, BuildRecord>,
+}
+
+/// Files written for a single build. A `None` size means the resource was not
+/// available on the server (e.g. EnvInject plugin not installed).
+#[derive(Serialize, Deserialize)]
+pub struct BuildRecord {
+    pub build_info: u64,
+    pub console_text: Option<u64>,
+    pub injected_env_vars: Option<u64>,
+    /// Resources skipped because they were forbidden, which leave the build
+    /// incomplete
+    #[serde(default)]
+    pub forbidden: Vec<String>,
+}
+
+/// Line of the progress manifest
+#[derive(Serialize, Deserialize)]
+struct ProgressEntry<R> {
+    /// Build path (e.g. "job/MyJob/1")
+    path: String,
+    #[serde(flatten)]
+    record: R,
+}
+
+impl Progress {
+    /// Open the progress manifest in the given output directory. When
+    /// `recover` is set, previously completed builds are loaded, otherwise
+    /// the manifest is started from scratch.
+    pub fn open(output_directory: &str, recover: bool) -> Result<Self> {
+        let filename = format!("{}/{}", output_directory, PROGRESS_FILE);
+        let mut completed = HashMap::new();
+        if recover {
+            if let Ok(file) = File::open(&filename) {
+                debug!("Loading progress from {}", filename);
+                for line in BufReader::new(file).lines() {
+                    // the last line may be truncated if the dump was killed
+                    let Ok(entry) = serde_json::from_str::<ProgressEntry<BuildRecord>>(
+                        &line.map_err(|e| Error::io(&filename, e))?,
+                    ) else {
+                        continue;
+                    };
+                    completed.insert(entry.path, entry.record);
+                }
+                debug!("Loaded {} completed builds", completed.len());
+            } else {
+                warn!("No progress manifest found in {}", output_directory);
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(recover)
+            .truncate(!recover)
+            .open(&filename)
+            .map_err(|e| Error::io(&filename, e))?;
+        Ok(Self {
+            file: Mutex::new(file),
+            completed,
+        })
+    }
+
+    /// Check whether the given build was completely dumped in a previous run
+    /// and its files are still intact
+    pub fn is_complete(&self, build_path: &str, build_directory: &str) -> bool {
+        match self.completed.get(build_path) {
+            Some(record) => record.verify(build_directory),
+            None => false,
+        }
+    }
+
+    /// Mark the given build as complete
+    pub fn record(&self, build_path: &str, record: &BuildRecord) -> Result<()> {
+        let entry = ProgressEntry {
+            path: build_path.to_string(),
+            record,
+        };
+        let mut line = serde_json::to_string(&entry).map_err(|e| Error::parse(PROGRESS_FILE, e))?;
+        line.push('\n');
+        let mut file = self.file.lock().unwrap();
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.flush())
+            .map_err(|e| Error::io(PROGRESS_FILE, e))?;
+        Ok(())
+    }
+}
+
+impl BuildRecord {
+    /// Check the files on disk against the recorded sizes, and that JSON
+    /// files can still be parsed. Builds with forbidden resources are never
+    /// complete.
+    fn verify(&self, build_directory: &str) -> bool {
+        if !self.forbidden.is_empty() {
+            debug!(
+                "Forbidden resources in {}: {}",
+                build_directory,
+                self.forbidden.join(", ")
+            );
+            return false;
+        }
+        let build_info_file = format!("{}/build_info.json", build_directory);
+        if !file_matches(&build_info_file, self.build_info, true) {
+            return false;
+        }
+        if let Some(size) = self.console_text {
+            let console_text_file = format!("{}/consoleText", build_directory);
+            if !file_matches(&console_text_file, size, false) {
+                return false;
+            }
+        }
+        if let Some(size) = self.injected_env_vars {
+            let injected_env_vars_file = format!("{}/injectedEnvVars.json", build_directory);
+            if !file_matches(&injected_env_vars_file, size, true) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Check that a file exists with the expected size and, optionally, that it
+/// contains valid JSON
+fn file_matches(filename: &str, size: u64, json: bool) -> bool {
+    match std::fs::metadata(filename) {
+        Ok(metadata) if metadata.len() == size => {}
+        _ => {
+            debug!("Incomplete file: {}", filename);
+            return false;
+        }
+    }
+    if json && crate::utils::load_json::<serde_json::Value>(filename).is_err() {
+        debug!("Corrupted file: {}", filename);
+        return false;
+    }
+    true
+}
@@ -0,0 +1,54 @@
+use super::secrets::{Finding, SecretScanner};
+use crate::error::{Error, Result};
+use log::debug;
+use std::path::Path;
+
+/// Files of a build dump that are scanned for secrets
+const SCANNED_FILES: [&str; 2] = ["consoleText", "injectedEnvVars.json"];
+
+/// Walk a dump directory and scan every consoleText and injectedEnvVars.json
+/// for secrets
+pub fn scan_directory(directory: &str) -> Result<Vec<Finding>> {
+    let scanner = SecretScanner::new();
+    let mut findings = Vec::new();
+    scan_directory_recursive(
+        Path::new(directory),
+        Path::new(directory),
+        &scanner,
+        &mut findings,
+    )?;
+    Ok(findings)
+}
+
+fn scan_directory_recursive(
+    root: &Path,
+    directory: &Path,
+    scanner: &SecretScanner,
+    findings: &mut Vec<Finding>,
+) -> Result<()> {
+    let io_error = |e| Error::io(directory.to_string_lossy(), e);
+    for entry in std::fs::read_dir(directory).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        if path.is_dir() {
+            scan_directory_recursive(root, &path, scanner, findings)?;
+            continue;
+        }
+        let Some(file) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !SCANNED_FILES.contains(&file) {
+            continue;
+        }
+        debug!("Scanning {}", path.display());
+        // consoleText is not guaranteed to be valid UTF-8
+        let content = std::fs::read(&path).map_err(|e| Error::io(path.to_string_lossy(), e))?;
+        let text = String::from_utf8_lossy(&content);
+        let build = directory
+            .strip_prefix(root)
+            .unwrap_or(directory)
+            .to_string_lossy()
+            .to_string();
+        findings.extend(scanner.scan_text(&text, &build, file));
+    }
+    Ok(())
+}
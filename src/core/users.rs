@@ -0,0 +1,168 @@
+use super::dump::{dumped_builds, Dumper};
+use super::models::{Action, Build, People, User, UserRecord};
+use crate::error::Result;
+use crate::utils::{self, concatenate_url, encode_path_segment};
+use futures::future::join_all;
+use log::{debug, info, warn};
+use percent_encoding::percent_decode_str;
+use reqwest::Url;
+use std::collections::BTreeMap;
+
+/// Listings of the users known to the server. Both are tried, as either may
+/// be missing or time out on large servers.
+const PEOPLE_LISTINGS: [&str; 2] = ["asynchPeople", "people"];
+/// Fields of the people listings
+const PEOPLE_TREE: &str = "users[lastChange,user[id,fullName,absoluteUrl]]";
+
+impl Dumper {
+    /// Dump the users known to the server to users.json. Users are listed
+    /// from the people pages and harvested from the causes and change sets of
+    /// the builds already dumped in the output directory, then completed with
+    /// their own page. Ids are case insensitive, as in Jenkins by default.
+    pub async fn dump_users(&self, output_directory: &str) -> Result<Vec<UserRecord>> {
+        let mut users: BTreeMap<String, UserRecord> = BTreeMap::new();
+        for listing in PEOPLE_LISTINGS {
+            let path = format!("{}/api/json?tree={}", listing, PEOPLE_TREE);
+            let people: People = match self.client.get_json(&path).await {
+                Ok(people) => people,
+                Err(e) => {
+                    warn!("Error listing users from {}: {}", listing, e);
+                    continue;
+                }
+            };
+            debug!("Found {} users in {}", people.users.len(), listing);
+            for person in people.users {
+                let user = person.user;
+                let id = Some(user.id.clone())
+                    .filter(|id| !id.is_empty())
+                    .or_else(|| user.absolute_url.as_deref().and_then(user_id_from_url));
+                let Some(id) = id else {
+                    continue;
+                };
+                let record = user_record(&mut users, &id, listing);
+                merge(&mut record.full_name, user.full_name);
+                merge(&mut record.url, user.absolute_url);
+                merge_activity(record, person.last_change);
+            }
+        }
+
+        let builds = dumped_builds::<Build>(output_directory)?;
+        if builds.is_empty() {
+            info!(
+                "No builds dumped in {}, users are not harvested from builds",
+                output_directory
+            );
+        }
+        for (_, build) in &builds {
+            harvest_build(build, &mut users);
+        }
+        info!("Found {} users, retrieving their details", users.len());
+
+        // Complete users with their own page, which may be forbidden
+        let tasks = users.values().map(|record| self.get_user(&record.id));
+        let results: Vec<Result<User>> = join_all(tasks).await;
+        for (record, result) in users.values_mut().zip(results) {
+            match result {
+                Ok(user) => {
+                    if !record.sources.iter().any(|source| source == "user") {
+                        record.sources.push("user".to_string());
+                    }
+                    if let Some(email) = user.email() {
+                        record.email = Some(email.to_string());
+                    }
+                    record.full_name = user.full_name.or(record.full_name.take());
+                    record.url = user.absolute_url.or(record.url.take());
+                }
+                Err(e) => debug!("Error retrieving user {}: {}", record.id, e),
+            }
+        }
+
+        // Save users to file
+        let users: Vec<UserRecord> = users.into_values().collect();
+        let users_file = format!("{}/users.json", output_directory);
+        debug!("Saving users to {}", users_file);
+        utils::save_json(&users, &users_file)?;
+        Ok(users)
+    }
+
+    /// Get the page of the given user
+    async fn get_user(&self, id: &str) -> Result<User> {
+        let path = format!("user/{}/api/json", encode_path_segment(id));
+        self.client
+            .get_json_url(&concatenate_url(self.client.url(), &path)?)
+            .await
+    }
+}
+
+/// Add the users who started a build or authored its changes
+fn harvest_build(build: &Build, users: &mut BTreeMap<String, UserRecord>) {
+    for action in &build.actions {
+        let Action::Cause(action) = action else {
+            continue;
+        };
+        for cause in &action.causes {
+            let Some(id) = &cause.user_id else {
+                continue;
+            };
+            let record = user_record(users, id, "cause");
+            merge(&mut record.full_name, cause.user_name.clone());
+            merge_activity(record, build.timestamp);
+        }
+    }
+    for change_set in build.change_set.iter().chain(&build.change_sets) {
+        for item in &change_set.items {
+            let Some(author) = &item.author else {
+                continue;
+            };
+            let Some(id) = author.absolute_url.as_deref().and_then(user_id_from_url) else {
+                continue;
+            };
+            let record = user_record(users, &id, "changeSet");
+            merge(&mut record.full_name, author.full_name.clone());
+            merge(&mut record.email, item.author_email.clone());
+            merge(&mut record.url, author.absolute_url.clone());
+            merge_activity(record, item.timestamp.or(build.timestamp));
+        }
+    }
+}
+
+/// Get the record of the given user, creating it if needed, and note where
+/// it was seen
+fn user_record<'a>(
+    users: &'a mut BTreeMap<String, UserRecord>,
+    id: &str,
+    source: &str,
+) -> &'a mut UserRecord {
+    let record = users
+        .entry(id.to_lowercase())
+        .or_insert_with(|| UserRecord {
+            id: id.to_string(),
+            ..Default::default()
+        });
+    if !record.sources.iter().any(|seen| seen == source) {
+        record.sources.push(source.to_string());
+    }
+    record
+}
+
+/// Fill a field that is not known yet
+fn merge(field: &mut Option<String>, value: Option<String>) {
+    if field.is_none() {
+        *field = value.filter(|value| !value.is_empty());
+    }
+}
+
+/// Keep the most recent activity of the user
+fn merge_activity(record: &mut UserRecord, timestamp: Option<i64>) {
+    record.last_activity = record.last_activity.max(timestamp);
+}
+
+/// Get the user id from the url of a user page (e.g.
+/// "https://host/user/alice%40corp.com/" -> "alice@corp.com")
+fn user_id_from_url(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let mut segments = url.path_segments()?;
+    segments.find(|segment| *segment == "user")?;
+    let id = segments.next().filter(|id| !id.is_empty())?;
+    Some(percent_decode_str(id).decode_utf8_lossy().to_string())
+}
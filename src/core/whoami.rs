@@ -0,0 +1,165 @@
+use super::client::JenkinsClient;
+use super::models::{Item, Jenkins, User, WhoAmI};
+use crate::error::{Error, Result};
+use crate::utils::concatenate_url;
+use log::debug;
+use serde::Serialize;
+
+/// Name of the report inside the output directory
+pub const WHOAMI_FILE: &str = "whoami.json";
+
+/// Identity behind the current credentials and what it is allowed to do
+#[derive(Debug, Serialize)]
+pub struct WhoAmIReport {
+    pub url: String,
+    pub identity: WhoAmI,
+    /// User record, missing for anonymous and unknown users
+    pub user: Option<User>,
+    /// Permission matrix, one probe per permission
+    pub permissions: Vec<PermissionProbe>,
+}
+
+/// Outcome of a request testing a single permission
+#[derive(Debug, Serialize)]
+pub struct PermissionProbe {
+    /// Permission tested (e.g. "job-configure")
+    pub permission: &'static str,
+    /// Url requested, missing if there was nothing to request
+    pub url: Option<String>,
+    pub status: ProbeStatus,
+    /// Why the permission is not allowed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeStatus {
+    Allowed,
+    Denied,
+    /// The endpoint does not exist (e.g. plugin not installed, no jobs)
+    Unavailable,
+    /// The request failed for another reason
+    Error,
+}
+
+/// Query the identity of the client and probe what it can do. Only GET
+/// requests are sent, none of them changes anything on the server.
+pub async fn whoami(client: &JenkinsClient) -> Result<WhoAmIReport> {
+    let identity: WhoAmI = client.get_json("whoAmI/api/json").await?;
+    let user = if identity.anonymous {
+        None
+    } else {
+        match client.get_json::<User>("me/api/json").await {
+            Ok(user) => Some(user),
+            Err(e) => {
+                debug!("Error retrieving current user: {}", e);
+                None
+            }
+        }
+    };
+
+    let mut permissions = vec![probe(client, "read", "api/json?tree=url").await];
+    // job permissions are probed on the first job found
+    match first_job(client).await {
+        Some(job_url) => {
+            let config_url = concatenate_url(&job_url, "config.xml")?;
+            permissions.push(probe(client, "job-configure", &config_url).await);
+            permissions.push(probe_build(client, &job_url).await);
+        }
+        None => {
+            for permission in ["job-configure", "job-build"] {
+                permissions.push(PermissionProbe {
+                    permission,
+                    url: None,
+                    status: ProbeStatus::Unavailable,
+                    detail: Some("No job visible to probe".to_string()),
+                });
+            }
+        }
+    }
+    permissions.push(probe(client, "script-console", "script").await);
+    permissions.push(probe(client, "manage", "manage/").await);
+    permissions.push(
+        probe(
+            client,
+            "credentials-view",
+            "credentials/store/system/domain/_/api/json?tree=credentials[id]",
+        )
+        .await,
+    );
+
+    Ok(WhoAmIReport {
+        url: client.url().to_string(),
+        identity,
+        user,
+        permissions,
+    })
+}
+
+/// Request the given path or url and derive the permission status from the
+/// response
+async fn probe(client: &JenkinsClient, permission: &'static str, path: &str) -> PermissionProbe {
+    let url = concatenate_url(client.url(), path).unwrap_or_else(|_| path.to_string());
+    debug!("Probing {} permission with {}", permission, url);
+    let (status, detail) = match client.get_url(&url).await {
+        Ok(_) => (ProbeStatus::Allowed, None),
+        Err(e) => (failed_status(&e), Some(e.to_string())),
+    };
+    PermissionProbe {
+        permission,
+        url: Some(url),
+        status,
+        detail,
+    }
+}
+
+/// Probe the build permission of a job. Triggering a build would change the
+/// server, so the job page is checked for its build link instead.
+async fn probe_build(client: &JenkinsClient, job_url: &str) -> PermissionProbe {
+    let (status, detail) = match client.get_url(job_url).await {
+        Ok(page) if page.contains("build?delay=0sec") || page.contains("buildWithParameters") => {
+            (ProbeStatus::Allowed, None)
+        }
+        // disabled jobs have no build link either
+        Ok(_) => (
+            ProbeStatus::Denied,
+            Some("No build link on the job page".to_string()),
+        ),
+        Err(e) => (failed_status(&e), Some(e.to_string())),
+    };
+    PermissionProbe {
+        permission: "job-build",
+        url: Some(job_url.to_string()),
+        status,
+        detail,
+    }
+}
+
+/// Status of a probe whose request failed
+fn failed_status(e: &Error) -> ProbeStatus {
+    if e.is_auth() {
+        ProbeStatus::Denied
+    } else if e.is_not_found() {
+        ProbeStatus::Unavailable
+    } else {
+        ProbeStatus::Error
+    }
+}
+
+/// Get the url of the first top-level job, preferring buildable jobs over
+/// folders
+async fn first_job(client: &JenkinsClient) -> Option<String> {
+    let root: Jenkins = match client.get_json("api/json?tree=jobs[name,url]").await {
+        Ok(root) => root,
+        Err(e) => {
+            debug!("Error listing jobs to probe: {}", e);
+            return None;
+        }
+    };
+    root.jobs
+        .iter()
+        .find(|item| matches!(item, Item::Job(_)))
+        .or(root.jobs.first())
+        .map(|item| client.rewrite_url(item.url()))
+}
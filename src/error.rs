@@ -0,0 +1,161 @@
+use reqwest::StatusCode;
+
+/// Result type used across the crate
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Error raised while talking to Jenkins or handling dumps. Each variant
+/// carries the url, file or job it happened on, and tells callers whether it
+/// is worth retrying or skipping.
+#[derive(Debug)]
+pub enum Error {
+    /// The request could not be sent or its response could not be read
+    /// (e.g. connection refused, timeout)
+    Http { url: String, source: reqwest::Error },
+    /// The server answered with an unsuccessful status, other than an
+    /// authentication or permission failure
+    Status { url: String, status: StatusCode },
+    /// Authentication or permission failure
+    Auth { url: String, kind: AuthError },
+    /// A response or file could not be parsed, or lacks an expected field
+    Parse { context: String, message: String },
+    /// A file or directory could not be read or written
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    /// An invalid url was given or returned by the server
+    Url {
+        url: String,
+        source: url::ParseError,
+    },
+    /// Error retrieving a job, with its path (e.g. "job/MyFolder/job/MyJob")
+    Job { path: String, source: Box<Error> },
+    /// Nothing was found to work on (e.g. "jobs", "builds")
+    Empty(&'static str),
+}
+
+/// Kind of authentication or permission failure
+#[derive(Debug)]
+pub enum AuthError {
+    /// No credentials were given, or they were ignored, and the resource
+    /// requires authentication
+    Unauthenticated,
+    /// The credentials were rejected
+    BadCredentials,
+    /// The current user lacks a permission, named by Jenkins when it can
+    MissingPermission {
+        user: Option<String>,
+        permission: Option<String>,
+    },
+}
+
+impl Error {
+    /// Create an IO error for the given path
+    pub fn io(path: impl Into<String>, source: std::io::Error) -> Self {
+        Error::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    /// Create a parse error for the given url or file
+    pub fn parse(context: impl Into<String>, message: impl std::fmt::Display) -> Self {
+        Error::Parse {
+            context: context.into(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Attach the path of the job the error happened on
+    pub fn in_job(self, path: impl Into<String>) -> Self {
+        Error::Job {
+            path: path.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Get the underlying error, without the job context
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Job { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    /// Check whether the error is an authentication or permission problem,
+    /// which retrying does not solve
+    pub fn is_auth(&self) -> bool {
+        matches!(self.root(), Error::Auth { .. })
+    }
+
+    /// Check whether the requested resource does not exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self.root(),
+            Error::Status {
+                status: StatusCode::NOT_FOUND,
+                ..
+            }
+        )
+    }
+
+    /// Check whether the error may be transient: network failures, including
+    /// truncated responses, server errors and throttling. Parse errors are
+    /// not, as the same response fails to parse again.
+    pub fn is_retryable(&self) -> bool {
+        match self.root() {
+            Error::Http { .. } => true,
+            Error::Status { status, .. } => {
+                status.is_server_error()
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+                    || *status == StatusCode::REQUEST_TIMEOUT
+            }
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Http { url, source } => write!(f, "Request to {} failed: {}", url, source),
+            Error::Status { url, status } if *status == StatusCode::NOT_FOUND => {
+                write!(f, "Not found: {}", url)
+            }
+            Error::Status { url, status } if status.is_server_error() => {
+                write!(f, "Server error {} for {}", status, url)
+            }
+            Error::Status { url, status } => write!(f, "Error {} for {}", status, url),
+            Error::Auth { url, kind } => match kind {
+                AuthError::Unauthenticated => write!(f, "Authentication required for {}", url),
+                AuthError::BadCredentials => write!(f, "Invalid credentials for {}", url),
+                AuthError::MissingPermission { user, permission } => write!(
+                    f,
+                    "{} is missing the {} permission for {}",
+                    user.as_deref().unwrap_or("current user"),
+                    permission.as_deref().unwrap_or("required"),
+                    url
+                ),
+            },
+            Error::Parse { context, message } => {
+                write!(f, "Error parsing {}: {}", context, message)
+            }
+            Error::Io { path, source } => write!(f, "Error accessing {}: {}", path, source),
+            Error::Url { url, source } => write!(f, "Invalid url {}: {}", url, source),
+            Error::Job { path, source } => write!(f, "{}: {}", path, source),
+            Error::Empty(what) => write!(f, "No {} found", what),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Http { source, .. } => Some(source),
+            Error::Io { source, .. } => Some(source),
+            Error::Url { source, .. } => Some(source),
+            Error::Job { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Jenkins enumeration and dumping, as used by the jenkenpwn CLI.
+//!
+//! ```no_run
+//! use futures::StreamExt;
+//! use jenkenpwn::{Authentication, Dumper, JenkinsClient};
+//!
+//! # async fn run() -> jenkenpwn::Result<()> {
+//! let client = JenkinsClient::builder("https://jenkins.example.com/")
+//!     .authentication(Authentication::ApiToken {
+//!         username: "admin".to_string(),
+//!         token: "token".to_string(),
+//!     })
+//!     .max_requests(10)
+//!     .build()?;
+//! let mut dumper = Dumper::new(client).max_builds(Some(5));
+//! dumper.detect_root().await?;
+//!
+//! let jobs = dumper.jobs(false);
+//! futures::pin_mut!(jobs);
+//! while let Some(job) = jobs.next().await {
+//!     println!("{}", job?.url);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod core;
+pub mod error;
+pub mod utils;
+
+pub use crate::core::client::{Authentication, ClientBuilder, JenkinsClient};
+pub use crate::core::dump::{BuildsSummary, Dumper};
+pub use crate::core::models;
+pub use crate::error::{Error, Result};
@@ -8,5 +8,6 @@ pub fn init_logger(verbose: bool) {
         log::LevelFilter::Info
     };
 
-    env_logger::builder().filter_level(log_level).init();
+    // the logger may already be initialized by another client
+    let _ = env_logger::builder().filter_level(log_level).try_init();
 }
@@ -1,63 +1,72 @@
-mod core;
+mod cli;
 mod logger;
-mod utils;
 
-use crate::core::dump::Dumper;
+use crate::cli::{AuthArgs, Cli};
+use crate::logger::init_logger;
 use clap::Parser;
+use jenkenpwn::core::advisories::AdvisoryDatabase;
+use jenkenpwn::core::compare::{self, Visibility};
+use jenkenpwn::core::{fingerprint, scan, whoami};
+use jenkenpwn::utils;
+use jenkenpwn::{Authentication, BuildsSummary, ClientBuilder, Dumper, JenkinsClient};
 use log::{info, warn};
+use reqwest::Url;
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
 use tokio;
-use utils::Cli;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    init_logger(cli.verbose);
 
     // run the subcommand
     match &cli.subcmd {
-        utils::SubCommand::Dump(args) => {
-            let dumper = if let (Some(username), Some(password)) = (&args.username, &args.password)
-            {
-                Dumper::with_credentials(
-                    args.url.clone(),
-                    username.clone(),
-                    password.clone(),
-                    cli.verbose,
-                    cli.insecure,
-                )
-            } else {
-                Dumper::new(args.url.clone(), cli.verbose, cli.insecure)
-            };
+        cli::SubCommand::Dump(args) => {
+            let client = client_builder(&args.url, &args.auth, cli.insecure)?
+                .max_requests(args.max_requests)
+                .requests_per_second(args.rps);
+            let mut dumper = Dumper::new(client.build()?).max_builds(args.max_builds);
+            if let Err(e) = dumper.detect_root().await {
+                warn!("Error detecting Jenkins root: {}", e);
+            }
             utils::create_directory(&args.output)?;
             match args.resource {
-                utils::DumpResource::Builds => {
-                    match &args.jobs {
-                        Some(jobs_file) => {
-                            let result = dumper
-                                .dump_builds_from_file(&jobs_file, &args.output)
-                                .await;
-                            match result {
-                                Ok(_) => {
-                                    info!("Builds dumped successfully");
-                                }
-                                Err(e) => {
-                                    warn!("Error dumping builds: {}", e);
-                                }
+                cli::DumpResource::Builds => match &args.jobs {
+                    Some(jobs_file) => {
+                        let result = dumper
+                            .dump_builds_from_file(
+                                jobs_file,
+                                &args.output,
+                                args.last,
+                                args.recover,
+                                args.scan,
+                            )
+                            .await;
+                        match result {
+                            Ok(summary) => {
+                                log_builds_summary(&summary, &args.output);
+                            }
+                            Err(e) => {
+                                warn!("Error dumping builds: {}", e);
                             }
                         }
-                        None => {
-                            let result = dumper.dump_builds(&args.output, args.last).await;
-                            match result {
-                                Ok(_) => {
-                                    info!("Builds dumped successfully");
-                                }
-                                Err(e) => {
-                                    warn!("Error dumping builds: {}", e);
-                                }
+                    }
+                    None => {
+                        let result = dumper
+                            .dump_builds(&args.output, args.last, args.recover, args.scan)
+                            .await;
+                        match result {
+                            Ok(summary) => {
+                                log_builds_summary(&summary, &args.output);
+                            }
+                            Err(e) => {
+                                warn!("Error dumping builds: {}", e);
                             }
                         }
                     }
-                }
-                utils::DumpResource::Jobs => {
+                },
+                cli::DumpResource::Jobs => {
                     let result = dumper.dump_jobs(&args.output, args.last).await;
                     match result {
                         Ok(_) => {
@@ -68,12 +77,291 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         }
                     }
                 }
-                utils::DumpResource::Views => {
-                    unimplemented!("Views are not implemented yet")
+                cli::DumpResource::Views => {
+                    let result = dumper.dump_views(&args.output).await;
+                    match result {
+                        Ok(_) => {
+                            info!("Views dumped successfully");
+                        }
+                        Err(e) => {
+                            warn!("Error dumping views: {}", e);
+                        }
+                    }
+                }
+                cli::DumpResource::Plugins => {
+                    let database = AdvisoryDatabase::load(args.advisories.as_deref())?;
+                    let result = dumper.dump_plugins(&args.output, &database).await;
+                    match result {
+                        Ok(plugins) => {
+                            for plugin in plugins.iter().filter(|p| !p.advisories.is_empty()) {
+                                let ids: Vec<&str> =
+                                    plugin.advisories.iter().map(|a| a.id.as_str()).collect();
+                                warn!(
+                                    "{} {} is affected by {}",
+                                    plugin.short_name,
+                                    plugin.version.as_deref().unwrap_or("(unknown version)"),
+                                    ids.join(", ")
+                                );
+                            }
+                            info!("{} plugins dumped successfully", plugins.len());
+                        }
+                        Err(e) => {
+                            warn!("Error dumping plugins: {}", e);
+                        }
+                    }
+                }
+                cli::DumpResource::Users => {
+                    let result = dumper.dump_users(&args.output).await;
+                    match result {
+                        Ok(users) => {
+                            info!("{} users dumped successfully", users.len());
+                        }
+                        Err(e) => {
+                            warn!("Error dumping users: {}", e);
+                        }
+                    }
+                }
+                cli::DumpResource::Nodes => {
+                    let result = dumper.dump_nodes(&args.output).await;
+                    match result {
+                        Ok(nodes) => {
+                            for job in nodes.iter().filter(|n| n.built_in).flat_map(|n| &n.jobs) {
+                                warn!("Job running on the controller: {}", job);
+                            }
+                            info!("{} nodes dumped successfully", nodes.len());
+                        }
+                        Err(e) => {
+                            warn!("Error dumping nodes: {}", e);
+                        }
+                    }
+                }
+                cli::DumpResource::Credentials => {
+                    let result = dumper.dump_credentials(&args.output).await;
+                    match result {
+                        Ok(credentials) => {
+                            for credential in &credentials {
+                                let jobs: BTreeSet<&str> =
+                                    credential.jobs.iter().map(|u| u.job.as_str()).collect();
+                                info!(
+                                    "{} ({}) is referenced by {} jobs",
+                                    credential.id,
+                                    credential.type_name.as_deref().unwrap_or("unknown type"),
+                                    jobs.len()
+                                );
+                            }
+                            info!("{} credentials dumped successfully", credentials.len());
+                        }
+                        Err(e) => {
+                            warn!("Error dumping credentials: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+        cli::SubCommand::Whoami(args) => {
+            let mut client = client_builder(&args.url, &args.auth, cli.insecure)?.build()?;
+            if let Err(e) = client.detect_root().await {
+                warn!("Error detecting Jenkins root: {}", e);
+            }
+            utils::create_directory(&args.output)?;
+            let report = whoami::whoami(&client).await?;
+            if report.identity.anonymous {
+                info!("Authenticated as anonymous");
+            } else {
+                info!("Authenticated as {}", report.identity.name);
+            }
+            if !report.identity.authorities.is_empty() {
+                info!("Authorities: {}", report.identity.authorities.join(", "));
+            }
+            for probe in &report.permissions {
+                match &probe.detail {
+                    Some(detail) => info!("{}: {:?} ({})", probe.permission, probe.status, detail),
+                    None => info!("{}: {:?}", probe.permission, probe.status),
+                }
+            }
+            let output = format!("{}/{}", args.output, whoami::WHOAMI_FILE);
+            utils::save_json(&report, &output)?;
+            info!("Saved identity and permissions to {}", output);
+        }
+        cli::SubCommand::Compare(args) => {
+            let authentication = authentication(&args.auth, &args.url)?
+                .ok_or("Credentials are required to compare with anonymous access")?;
+            let client = || {
+                JenkinsClient::builder(args.url.clone())
+                    .insecure(cli.insecure)
+                    .max_requests(args.max_requests)
+                    .requests_per_second(args.rps)
+            };
+            let mut anonymous = Dumper::new(client().build()?);
+            let mut authenticated = Dumper::new(client().authentication(authentication).build()?);
+            for dumper in [&mut anonymous, &mut authenticated] {
+                if let Err(e) = dumper.detect_root().await {
+                    warn!("Error detecting Jenkins root: {}", e);
                 }
             }
+            utils::create_directory(&args.output)?;
+            let report =
+                compare::compare(&anonymous, &authenticated, &args.output, args.last).await?;
+            log_visibility("Visible without authentication", &report.anonymous);
+            for endpoint in &report.anonymous.endpoints {
+                warn!("Endpoint readable without authentication: {}", endpoint);
+            }
+            log_visibility("Visible only with credentials", &report.authenticated_only);
+            if !report.anonymous_only.is_empty() {
+                log_visibility("Hidden from the credentials", &report.anonymous_only);
+            }
+            let output = format!("{}/{}", args.output, compare::VISIBILITY_FILE);
+            utils::save_json(&report, &output)?;
+            info!("Saved visibility comparison to {}", output);
+        }
+        cli::SubCommand::Fingerprint(args) => {
+            let client = client_builder(&args.url, &args.auth, cli.insecure)?.build()?;
+            let database = AdvisoryDatabase::load(args.advisories.as_deref())?;
+            utils::create_directory(&args.output)?;
+            let report = fingerprint::fingerprint(&client, &database).await?;
+            match (&report.version, report.lts) {
+                (Some(version), Some(true)) => info!("Jenkins {} (LTS)", version),
+                (Some(version), _) => info!("Jenkins {} (weekly)", version),
+                (None, _) => warn!("Jenkins version not found"),
+            }
+            for advisory in &report.advisories {
+                warn!(
+                    "{} ({}): {} [{}], fixed in {}{}",
+                    advisory.id,
+                    advisory.cves.join(", "),
+                    advisory.title,
+                    advisory.severity,
+                    advisory.fixed,
+                    advisory
+                        .fixed_lts
+                        .iter()
+                        .map(|fixed| format!(", LTS {}", fixed))
+                        .collect::<String>()
+                );
+            }
+            if report.version.is_some() {
+                info!(
+                    "{} advisories apply, database updated on {}",
+                    report.advisories.len(),
+                    report.advisories_updated
+                );
+            }
+            let output = format!("{}/{}", args.output, fingerprint::FINGERPRINT_FILE);
+            utils::save_json(&report, &output)?;
+            info!("Saved fingerprint to {}", output);
+        }
+        cli::SubCommand::Scan(args) => {
+            let findings = scan::scan_directory(&args.directory)?;
+            for finding in &findings {
+                warn!("{}", finding);
+            }
+            let output = args
+                .output
+                .clone()
+                .unwrap_or_else(|| format!("{}/findings.json", args.directory));
+            utils::save_json(&findings, &output)?;
+            info!("Found {} secrets, saved to {}", findings.len(), output);
         }
     }
 
     Ok(())
 }
+
+/// Start building a client for the given url, with the credentials given on
+/// the command line
+fn client_builder(
+    url: &str,
+    auth: &AuthArgs,
+    insecure: bool,
+) -> Result<ClientBuilder, Box<dyn std::error::Error>> {
+    let mut builder = JenkinsClient::builder(url).insecure(insecure);
+    if let Some(authentication) = authentication(auth, url)? {
+        builder = builder.authentication(authentication);
+    }
+    Ok(builder)
+}
+
+/// Build the authentication from the credential arguments, if any was given.
+/// Credentials come from the command line or environment, then from the
+/// netrc file, and the password is prompted for when only a username is
+/// known.
+fn authentication(
+    args: &AuthArgs,
+    url: &str,
+) -> Result<Option<Authentication>, Box<dyn std::error::Error>> {
+    let mut username = args.username.clone();
+    let mut password = args.password.clone();
+    if let (Some(netrc), None, None) = (&args.netrc, &password, &args.token) {
+        let host = Url::parse(url)?.host_str().unwrap_or_default().to_string();
+        match utils::netrc_credentials(netrc, &host)? {
+            Some((login, netrc_password)) => {
+                if username.is_none() || username.as_deref() == Some(login.as_str()) {
+                    username = Some(login);
+                    password = Some(netrc_password);
+                }
+            }
+            None => warn!("No credentials found for {} in {}", host, netrc),
+        }
+    }
+
+    if let Some(username) = username {
+        if let Some(token) = &args.token {
+            return Ok(Some(Authentication::ApiToken {
+                username,
+                token: token.clone(),
+            }));
+        }
+        let password = match password {
+            Some(password) => password,
+            None if std::io::stdin().is_terminal() => {
+                rpassword::prompt_password(format!("Password for {}: ", username))?
+            }
+            None => return Err("Missing password or token for the given username".into()),
+        };
+        return Ok(Some(Authentication::Basic { username, password }));
+    }
+    if password.is_some() || args.token.is_some() {
+        return Err("A username is required along with the password or token".into());
+    }
+    if let Some(cookie) = &args.cookie {
+        return Ok(Some(Authentication::Cookie(cookie.clone())));
+    }
+    if let Some(token) = &args.bearer {
+        return Ok(Some(Authentication::Bearer(token.clone())));
+    }
+    if let Some((name, value)) = &args.header {
+        return Ok(Some(Authentication::Header {
+            name: name.clone(),
+            value: value.clone(),
+        }));
+    }
+    Ok(None)
+}
+
+/// Log how many resources of each kind a run could see
+fn log_visibility(label: &str, visibility: &Visibility) {
+    info!(
+        "{}: {} jobs, {} builds, {} views, {} endpoints",
+        label,
+        visibility.jobs.len(),
+        visibility.builds.len(),
+        visibility.views.len(),
+        visibility.endpoints.len()
+    );
+}
+
+/// Log the outcome of a builds dump
+fn log_builds_summary(summary: &BuildsSummary, output_directory: &str) {
+    info!(
+        "Builds dumped: {} succeeded, {} skipped, {} failed",
+        summary.succeeded, summary.skipped, summary.failed
+    );
+    if summary.failed > 0 {
+        // without --recover the progress manifest of this run is truncated
+        warn!(
+            "Failed builds saved to {0}/errors.json, retry them with \
+             --recover --jobs {0}/errors.json",
+            output_directory
+        );
+    }
+}